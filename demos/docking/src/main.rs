@@ -3,13 +3,14 @@ extern crate core;
 use chrono::{DateTime, Local};
 use egui::{Ui, ViewportBuilder, WidgetText};
 use egui_deferred_table::{
-    Action, AxisParameters, CellIndex, DeferredTable, DeferredTableRenderer, SimpleTupleRenderer,
-    apply_reordering,
+    Action, AxisParameters, CellIndex, ColumnGroup, DeferredTable, DeferredTableDataSource,
+    DeferredTableRenderer, FilterKind, SimpleTupleRenderer, TableDimensions, TreeRow,
+    apply_group_reordering, apply_reordering, build_rows_to_filter, build_sort_spec_ordering,
 };
 use egui_dock::{DockArea, DockState, NodeIndex};
 use log::Level;
 use shared::data::futurama;
-use shared::data::futurama::{RowType, format_value};
+use shared::data::futurama::{Kind, RowType, compare_values, format_value};
 use shared::growing::ui::GrowingTableState;
 use shared::sparse::ui::SparseTableState;
 use shared::spreadsheet::ui::SpreadsheetState;
@@ -63,6 +64,12 @@ impl Default for MyApp {
                     state: SpreadsheetState::default(),
                 },
             },
+            Tab {
+                name: "Tree",
+                kind: TabKind::TreeTable {
+                    state: TreeTableState::default(),
+                },
+            },
         ]);
 
         let [a, _b] = tree.main_surface_mut().split_left(
@@ -168,6 +175,7 @@ enum TabKind {
     Spreadsheet { state: SpreadsheetState },
     SparseTable { state: SparseTableState },
     GrowingTable { state: GrowingTableState },
+    TreeTable { state: TreeTableState },
     Log { state: LogState },
 }
 
@@ -189,6 +197,9 @@ impl TabKind {
             TabKind::GrowingTable { state } => {
                 contents_growing_table(ui, context, state);
             }
+            TabKind::TreeTable { state } => {
+                contents_tree_table(ui, context, state);
+            }
             TabKind::Log { state } => {
                 contents_log(ui, context, state);
             }
@@ -273,6 +284,19 @@ fn contents_advanced_table(ui: &mut Ui, context: &mut TabContext, state: &mut Ad
     ui.label("Try dragging the column and rows headers to reorder them.");
     ui.label("Some columns are expandable, try resizing the window.");
     ui.label("Some columns are resizable, try resizing them.");
+    ui.label(
+        "Click on the column headers to sort the rows, shift+click to sort by multiple columns.",
+    );
+    ui.label("Click on the filter buttons in the column headers to filter the rows.");
+    ui.label(
+        "Click and drag to select cells, shift+click to extend and ctrl+click to add a selection.",
+    );
+    ui.label("Click the top-left corner, or press Ctrl+A, to select all the cells, Escape to clear the selection.");
+    ui.label("Try dragging the column group headers onto a column header to move the whole group.");
+    ui.checkbox(
+        &mut state.group_by_kind,
+        "Group the rows by kind, sort by kind to get one group per kind.",
+    );
 
     ui.separator();
 
@@ -348,7 +372,25 @@ fn contents_advanced_table(ui: &mut Ui, context: &mut TabContext, state: &mut Ad
     let column_params = futurama::fields()
         .iter()
         .zip(FIELD_PARAMS)
-        .map(|(field_name, field_params)| {
+        .enumerate()
+        .map(|(index, (field_name, field_params))| {
+            let filter_kind = match index {
+                1 => FilterKind::Values(
+                    [
+                        Kind::Human,
+                        Kind::Alien,
+                        Kind::Mutant,
+                        Kind::Robot,
+                        Kind::Animal,
+                    ]
+                    .iter()
+                    .map(|kind| kind.to_string())
+                    .collect(),
+                ),
+                5 | 6 | 7 => FilterKind::Number,
+                _ => FilterKind::Text,
+            };
+
             AxisParameters::default()
                 .name(field_name.to_string())
                 .resizable(field_params.resizable)
@@ -356,12 +398,30 @@ fn contents_advanced_table(ui: &mut Ui, context: &mut TabContext, state: &mut Ad
                 .minimum_dimension(field_params.minimum_width)
                 .maximum_dimension(field_params.maximum_width)
                 .expandable(field_params.expandable)
+                .filter(filter_kind)
         })
         .collect::<Vec<_>>();
 
-    let (_response, actions) = DeferredTable::new(ui.make_persistent_id("table_1"))
+    let column_groups = [
+        ColumnGroup::new("Character", [0, 1, 2]),
+        ColumnGroup::new("Appearances", [5, 6, 7]),
+        ColumnGroup::new("Details", [3, 4, 5, 6, 7]).level(1),
+    ];
+
+    let mut table = DeferredTable::new(ui.make_persistent_id("table_1"))
         .column_parameters(&column_params)
-        .show(ui, &mut data_source, &mut state.renderer);
+        .column_groups(&column_groups)
+        .sortable_columns()
+        .selectable_cells();
+    if state.group_by_kind {
+        table = table
+            .group_rows_by(|source: &&[RowType], index| source[index].1.to_string())
+            .group_aggregates(|source: &&[RowType], rows| {
+                let appearances = rows.iter().map(|row| source[*row].7).sum::<u32>();
+                format!("{} appearances", appearances)
+            });
+    }
+    let (_response, actions) = table.show(ui, &mut data_source, &mut state.renderer);
 
     for action in actions {
         match action {
@@ -370,12 +430,76 @@ fn contents_advanced_table(ui: &mut Ui, context: &mut TabContext, state: &mut Ad
                 Level::Info,
                 format!("Cell clicked. cell: {:?}", cell_index),
             ),
+            Action::CellDoubleClicked(cell_index) => example_log(
+                context.log_entries,
+                Level::Info,
+                format!("Cell double-clicked. cell: {:?}", cell_index),
+            ),
+            Action::CellSecondaryClicked { cell_index, pos } => example_log(
+                context.log_entries,
+                Level::Info,
+                format!(
+                    "Cell secondary-clicked. cell: {:?}, pos: {:?}",
+                    cell_index, pos
+                ),
+            ),
+            Action::HeaderSecondaryClicked { axis, index, .. } => example_log(
+                context.log_entries,
+                Level::Info,
+                format!(
+                    "Header secondary-clicked. axis: {:?}, index: {}",
+                    axis, index
+                ),
+            ),
             Action::ColumnReorder { from, to } => {
                 apply_reordering(&mut state.renderer.column_ordering, from, to);
             }
             Action::RowReorder { from, to } => {
                 apply_reordering(&mut state.renderer.row_ordering, from, to);
             }
+            Action::ColumnGroupReorder { group, to } => {
+                apply_group_reordering(
+                    &mut state.renderer.column_ordering,
+                    &column_groups[group].columns,
+                    to,
+                );
+            }
+            Action::CellSelectionChanged { selection } => example_log(
+                context.log_entries,
+                Level::Info,
+                format!("Cell selection changed. selection: {:?}", selection),
+            ),
+            Action::SortChanged { spec, .. } => {
+                let rows = context.data.as_slice();
+                state.renderer.row_ordering = (!spec.is_empty()).then(|| {
+                    build_sort_spec_ordering(rows.len(), &spec, &|column, a, b| {
+                        compare_values(&rows[a], &rows[b], column)
+                    })
+                });
+            }
+            Action::FilterChanged { filters, .. } => {
+                let rows = context.data.as_slice();
+                state.renderer.rows_to_filter =
+                    Some(build_rows_to_filter(rows.len(), &filters, |cell_index| {
+                        let row = &rows[cell_index.row];
+                        match cell_index.column {
+                            5 => row.5.into(),
+                            6 => row.6.into(),
+                            7 => row.7.into(),
+                            column => format_value(row, column).into(),
+                        }
+                    }));
+            }
+            Action::RowGroupExpansionChanged {
+                group, expanded, ..
+            } => example_log(
+                context.log_entries,
+                Level::Info,
+                format!(
+                    "Row group expansion changed. group: {}, expanded: {}",
+                    group, expanded
+                ),
+            ),
             _ => {
                 // ignored
             }
@@ -386,13 +510,15 @@ fn contents_advanced_table(ui: &mut Ui, context: &mut TabContext, state: &mut Ad
 #[derive(Default)]
 pub struct AdvancedTableState {
     renderer: AdvancedTableRenderer,
+    group_by_kind: bool,
 }
 
-/// Supports row and column reordering, no persistence between application restart.
+/// Supports row and column reordering, sorting and filtering, no persistence between application restart.
 #[derive(Default)]
 struct AdvancedTableRenderer {
     row_ordering: Option<Vec<usize>>,
     column_ordering: Option<Vec<usize>>,
+    rows_to_filter: Option<Vec<usize>>,
 }
 
 impl DeferredTableRenderer<&[RowType]> for AdvancedTableRenderer {
@@ -400,6 +526,14 @@ impl DeferredTableRenderer<&[RowType]> for AdvancedTableRenderer {
         ui.label(format_value(&source[cell_index.row], cell_index.column));
     }
 
+    fn cell_text(&self, cell_index: CellIndex, source: &&[RowType]) -> Option<String> {
+        Some(format_value(&source[cell_index.row], cell_index.column))
+    }
+
+    fn rows_to_filter(&self) -> Option<&[usize]> {
+        self.rows_to_filter.as_ref().map(|v| v.as_slice())
+    }
+
     fn row_ordering(&self) -> Option<&[usize]> {
         self.row_ordering.as_ref().map(|v| v.as_slice())
     }
@@ -407,6 +541,13 @@ impl DeferredTableRenderer<&[RowType]> for AdvancedTableRenderer {
     fn column_ordering(&self) -> Option<&[usize]> {
         self.column_ordering.as_ref().map(|v| v.as_slice())
     }
+
+    fn cell_context_menu(&self, ui: &mut Ui, cell_index: CellIndex, source: &&[RowType]) {
+        if ui.button("Copy").clicked() {
+            ui.ctx()
+                .copy_text(format_value(&source[cell_index.row], cell_index.column));
+        }
+    }
 }
 
 fn contents_log(ui: &mut Ui, context: &mut TabContext, _state: &mut LogState) {
@@ -484,6 +625,110 @@ fn contents_sparse_table(ui: &mut Ui, _context: &mut TabContext, state: &mut Spa
     shared::sparse::ui::handle_actions(actions, state);
 }
 
+fn contents_tree_table(ui: &mut Ui, context: &mut TabContext, state: &mut TreeTableState) {
+    ui.label("Click the triangles to collapse and expand the assemblies.");
+
+    let column_params = vec![
+        AxisParameters::default()
+            .name("Part".to_string())
+            .default_dimension(250.0),
+        AxisParameters::default()
+            .name("Quantity".to_string())
+            .default_dimension(100.0),
+    ];
+
+    let (_response, actions) = DeferredTable::new(ui.make_persistent_id("tree_table"))
+        .column_parameters(&column_params)
+        .show(ui, &mut state.data_source, &mut state.renderer);
+
+    for action in actions {
+        match action {
+            Action::RowExpansionChanged { row, expanded, .. } => example_log(
+                context.log_entries,
+                Level::Info,
+                format!(
+                    "Row expansion changed. row: {}, expanded: {}",
+                    row, expanded
+                ),
+            ),
+            _ => {
+                // ignored
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct TreeTableState {
+    data_source: PartsSource,
+    renderer: PartsRenderer,
+}
+
+/// A bill of materials, each part is listed after the assembly it belongs to.
+struct PartsSource {
+    /// name, quantity and the index of the parent assembly.
+    parts: Vec<(&'static str, u32, Option<usize>)>,
+}
+
+impl Default for PartsSource {
+    fn default() -> Self {
+        Self {
+            parts: vec![
+                ("Bicycle", 1, None),
+                ("Frame", 1, Some(0)),
+                ("Wheel", 2, Some(0)),
+                ("Rim", 1, Some(2)),
+                ("Spoke", 32, Some(2)),
+                ("Hub", 1, Some(2)),
+                ("Bearing", 2, Some(5)),
+                ("Axle", 1, Some(5)),
+                ("Tyre", 1, Some(2)),
+                ("Drivetrain", 1, Some(0)),
+                ("Chain", 1, Some(9)),
+                ("Crankset", 1, Some(9)),
+                ("Pedal", 2, Some(11)),
+                ("Spare parts", 1, None),
+                ("Inner tube", 2, Some(13)),
+            ],
+        }
+    }
+}
+
+impl DeferredTableDataSource for PartsSource {
+    fn get_dimensions(&self) -> TableDimensions {
+        TableDimensions {
+            row_count: self.parts.len(),
+            column_count: 2,
+        }
+    }
+
+    fn tree_row(&self, index: usize) -> Option<TreeRow> {
+        let parent = self.parts[index].2;
+        let depth = std::iter::successors(parent, |parent| self.parts[*parent].2).count();
+        let has_children = self.parts.iter().any(|part| part.2 == Some(index));
+
+        Some(TreeRow {
+            depth,
+            parent,
+            has_children,
+        })
+    }
+}
+
+#[derive(Default)]
+struct PartsRenderer {}
+
+impl DeferredTableRenderer<PartsSource> for PartsRenderer {
+    fn render_cell(&self, ui: &mut Ui, cell_index: CellIndex, source: &PartsSource) {
+        let (name, quantity, _) = source.parts[cell_index.row];
+        match cell_index.column {
+            0 => ui.label(name),
+            1 => ui.label(quantity.to_string()),
+            _ => unreachable!(),
+        };
+    }
+}
+
 fn contents_growing_table(ui: &mut Ui, context: &mut TabContext, state: &mut GrowingTableState) {
     shared::growing::ui::show_controls(ui, state);
     let (_response, actions) = shared::growing::ui::show_table(ui, state);
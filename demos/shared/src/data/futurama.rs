@@ -1,3 +1,4 @@
+use std::cmp::Ordering;
 use std::fmt::Display;
 
 #[derive(Debug)]
@@ -53,6 +54,20 @@ pub fn format_value(row: &RowType, index: usize) -> String {
     }
 }
 
+pub fn compare_values(a: &RowType, b: &RowType, index: usize) -> Ordering {
+    match index {
+        0 => a.0.cmp(&b.0),
+        1 => a.1.to_string().cmp(&b.1.to_string()),
+        2 => a.2.cmp(&b.2),
+        3 => a.3.cmp(&b.3),
+        4 => a.4.cmp(&b.4),
+        5 => a.5.cmp(&b.5),
+        6 => a.6.cmp(&b.6),
+        7 => a.7.cmp(&b.7),
+        _ => unreachable!(),
+    }
+}
+
 /// this data may be inaccurate, generated by an LLM
 pub fn characters() -> Vec<RowType> {
     vec![
@@ -28,6 +28,9 @@ pub struct GrowingSource<T> {
     last_accessed_at: DateTime<Local>,
     pending_operations: Vec<(DateTime<Local>, Operation)>,
     data: Vec<Vec<T>>,
+    /// a key for each row, so that the table's selection stays with the same rows when rows are inserted.
+    row_keys: Vec<u64>,
+    next_row_key: u64,
 }
 
 enum Operation {
@@ -70,6 +73,8 @@ impl<V> GrowingSource<CellState<V>> {
             if is_new_row {
                 let row = Vec::with_capacity(total_columns);
                 self.data.push(row);
+                self.row_keys.push(self.next_row_key);
+                self.next_row_key += 1;
             }
 
             let row = &mut self.data[row_index];
@@ -89,6 +94,29 @@ impl<V> GrowingSource<CellState<V>> {
         // here you could trigger a 'load' on another thread
     }
 
+    /// insert a row at the start
+    pub fn insert_row(&mut self) {
+        // this implementation can't insert if there are pending operations
+        if !self.pending_operations.is_empty() {
+            return;
+        }
+        let (_existing_rows, existing_columns) = self.dimensions();
+
+        let row = (0..existing_columns).map(|_| CellState::Loading).collect();
+        self.data.insert(0, row);
+        self.row_keys.insert(0, self.next_row_key);
+        self.next_row_key += 1;
+
+        self.pending_operations.push((
+            Local::now(),
+            Operation::Grow {
+                row_count: 1,
+                column_count: 0,
+            },
+        ));
+        // here you could trigger a 'load' on another thread
+    }
+
     /// shrink the source by rows/columns
     pub fn shrink(&mut self, row_count: usize, column_count: usize) {
         // this implementation can't shrink if there are pending operations
@@ -133,6 +161,8 @@ impl<T: Default> Default for GrowingSource<T> {
             pending_operations: vec![],
 
             data: vec![],
+            row_keys: vec![],
+            next_row_key: 0,
         }
     }
 }
@@ -211,6 +241,7 @@ impl GrowingSource<CellState<CellValue>> {
         if rows >= row_count {
             // this dummy implementation ignores the 'busy' state and just truncates rows/columns regardless
             self.data.truncate(rows - row_count);
+            self.row_keys.truncate(rows - row_count);
         }
         for row in self.data.iter_mut() {
             if columns >= column_count {
@@ -240,6 +271,14 @@ impl DeferredTableDataSource for GrowingSource<CellState<CellValue>> {
             column_count: columns,
         }
     }
+
+    fn has_row_keys(&self) -> bool {
+        true
+    }
+
+    fn row_key(&self, index: usize) -> u64 {
+        self.row_keys[index]
+    }
 }
 
 #[derive(Default)]
@@ -1,6 +1,6 @@
 use egui::Color32;
 use egui_deferred_table::{
-    CellIndex, DeferredTableDataSource, DeferredTableRenderer, TableDimensions,
+    CellIndex, CellSpan, DeferredTableDataSource, DeferredTableRenderer, TableDimensions,
 };
 use fastrand::Rng;
 use indexmap::map::IndexMap;
@@ -19,6 +19,8 @@ pub enum CellKind {
 #[derive(Debug)]
 pub struct SparseMapSource<V> {
     sparse_map: IndexMap<usize, IndexMap<usize, V>>,
+    /// the number of rows and columns spanned by merged cells, keyed by row and column index.
+    spans: IndexMap<(usize, usize), (usize, usize)>,
 
     // cached dimensions, lazily calculated
     extents: Cell<Option<TableDimensions>>,
@@ -71,6 +73,20 @@ impl DeferredTableRenderer<SparseMapSource<CellKind>> for SparseMapRenderer {
         }
     }
 
+    fn cell_text(
+        &self,
+        cell_index: CellIndex,
+        data_source: &SparseMapSource<CellKind>,
+    ) -> Option<String> {
+        data_source
+            .get(cell_index.row, cell_index.column)
+            .map(|value| match value {
+                CellKind::Float(value) => format!("{:.2}", value),
+                CellKind::Boolean(value) => value.to_string(),
+                CellKind::Text(value) => value.clone(),
+            })
+    }
+
     fn rows_to_filter(&self) -> Option<&[usize]> {
         self.rows_to_filter.as_ref().map(|v| v.as_slice())
     }
@@ -92,6 +108,7 @@ impl<V> SparseMapSource<V> {
     pub fn new() -> Self {
         Self {
             sparse_map: IndexMap::new(),
+            spans: IndexMap::new(),
             extents: Cell::new(None),
         }
     }
@@ -114,6 +131,12 @@ impl<V> SparseMapSource<V> {
             .get(&row_index)
             .and_then(|row| row.get(&column_index))
     }
+
+    /// merge the cell at the location with the cells below and to the right of it.
+    pub fn set_span(&mut self, row_index: usize, column_index: usize, rows: usize, columns: usize) {
+        self.spans
+            .insert((row_index, column_index), (rows, columns));
+    }
 }
 
 impl<V> DeferredTableDataSource for SparseMapSource<V> {
@@ -158,6 +181,25 @@ impl<V> DeferredTableDataSource for SparseMapSource<V> {
 
         extents
     }
+
+    fn has_cell_spans(&self) -> bool {
+        !self.spans.is_empty()
+    }
+
+    fn cell_span(&self, cell_index: CellIndex) -> Option<CellSpan> {
+        // the spans cover the following row and column indexes, i.e. the rows and columns are not re-ordered or filtered.
+        self.spans
+            .iter()
+            .find(|((row_index, column_index), (rows, columns))| {
+                (*row_index..row_index + rows).contains(&cell_index.row)
+                    && (*column_index..column_index + columns).contains(&cell_index.column)
+            })
+            .map(|(&(row, column), &(rows, columns))| CellSpan {
+                origin: CellIndex { row, column },
+                rows,
+                columns,
+            })
+    }
 }
 
 pub enum CellKindChoice {
@@ -1,6 +1,8 @@
 use crate::sparse::{CellKind, CellKindChoice, SparseMapRenderer, SparseMapSource, generate_data};
-use egui::{Response, Ui};
-use egui_deferred_table::{Action, DeferredTable, apply_reordering};
+use egui::{Id, Response, Ui};
+use egui_deferred_table::{
+    Action, AxisParameters, DeferredTable, DeferredTableState, apply_reordering,
+};
 use fastrand::Rng;
 use log::debug;
 use names::Generator;
@@ -10,6 +12,9 @@ pub struct SparseTableState {
     data: SparseMapSource<CellKind>,
     renderer: SparseMapRenderer,
 
+    /// only used for the columns pinned using the column header menu
+    column_parameters: Vec<AxisParameters>,
+
     ui_state: UiState,
 
     rng: Rng,
@@ -45,9 +50,16 @@ impl Default for SparseTableState {
             );
         }
 
+        // some merged cells
+        data.insert(0, 1, CellKind::Text("spans 4 columns".to_string()));
+        data.set_span(0, 1, 1, 4);
+        data.insert(2, 0, CellKind::Text("spans 3 rows".to_string()));
+        data.set_span(2, 0, 3, 1);
+
         Self {
             data,
             renderer,
+            column_parameters: vec![],
             ui_state: UiState::default(),
             rng,
             name_gen,
@@ -73,15 +85,24 @@ struct UiState {
     column_ordering_input: String,
 
     row_selections: BTreeSet<usize>,
+
+    /// the id of the table, available after the table has been shown
+    table_id: Option<Id>,
 }
 
 pub fn show_table(ui: &mut Ui, state: &mut SparseTableState) -> (Response, Vec<Action>) {
     let data_source = &mut state.data;
     let renderer = &mut state.renderer;
 
-    DeferredTable::new(ui.make_persistent_id("table_1"))
+    let table_id = ui.make_persistent_id("table_1");
+    state.ui_state.table_id = Some(table_id);
+
+    DeferredTable::new(table_id)
         .zero_based_headers()
         .highlight_hovered_cell()
+        .column_header_menu()
+        .column_chooser()
+        .column_parameters(&state.column_parameters)
         .show(ui, data_source, renderer)
 }
 
@@ -89,7 +110,7 @@ pub fn handle_actions(actions: Vec<Action>, state: &mut SparseTableState) {
     for action in actions {
         debug!("action: {:?}", action);
         match action {
-            Action::CellClicked(cell_index) => {
+            Action::CellClicked(cell_index) | Action::ActiveCellChanged { cell_index } => {
                 state.ui_state.column = cell_index.column;
                 state.ui_state.row = cell_index.row;
 
@@ -110,6 +131,39 @@ pub fn handle_actions(actions: Vec<Action>, state: &mut SparseTableState) {
                     }
                 }
             }
+            Action::CellDoubleClicked(_)
+            | Action::CellSecondaryClicked { .. }
+            | Action::CellMiddleClicked(_)
+            | Action::HeaderDoubleClicked { .. }
+            | Action::HeaderSecondaryClicked { .. }
+            | Action::HeaderMiddleClicked { .. }
+            | Action::ColumnGroupReorder { .. }
+            | Action::RowExpansionChanged { .. }
+            | Action::RowGroupExpansionChanged { .. }
+            | Action::Pasted { .. }
+            | Action::CopiedHtml { .. }
+            | Action::Undo(_)
+            | Action::Redo(_) => {
+                // not used by this example
+            }
+            Action::ColumnPinChanged { column, pin } => {
+                if state.column_parameters.len() <= column {
+                    state
+                        .column_parameters
+                        .resize_with(column + 1, AxisParameters::default);
+                }
+                state.column_parameters[column].pinned = pin;
+            }
+            Action::ColumnVisibilityChanged { hidden, .. } => {
+                let columns_to_filter = hidden.into_iter().collect::<Vec<_>>();
+
+                // Update UI to reflect changes
+                state.ui_state.filter_columns_input = list_to_string(&columns_to_filter);
+                state.renderer.columns_to_filter = Some(columns_to_filter);
+            }
+            Action::ColumnWidthChanged { .. } => {
+                // already applied by the table
+            }
             Action::ColumnReorder { from, to } => {
                 apply_reordering(&mut state.renderer.column_ordering, from, to);
 
@@ -127,6 +181,34 @@ pub fn handle_actions(actions: Vec<Action>, state: &mut SparseTableState) {
             Action::RowSelectionChanged { selection } => {
                 state.ui_state.row_selections = selection;
             }
+            Action::ColumnSelectionChanged { selection } => {
+                let _ = selection;
+                // column selection currently disabled
+                unreachable!()
+            }
+            Action::CellSelectionChanged { selection } => {
+                let _ = selection;
+                // cell selection currently disabled
+                unreachable!()
+            }
+            Action::SortChanged {
+                column,
+                direction,
+                spec,
+            } => {
+                let (_, _, _) = (column, direction, spec);
+                // sorting currently disabled
+                unreachable!()
+            }
+            Action::FilterChanged {
+                column,
+                filter,
+                filters,
+            } => {
+                let (_, _, _) = (column, filter, filters);
+                // filtering currently disabled
+                unreachable!()
+            }
         }
     }
 }
@@ -241,6 +323,17 @@ pub fn show_controls(ui: &mut Ui, state: &mut SparseTableState) {
         egui::Frame::group(ui.style()).show(ui, |ui| {
             ui.label("Row selections");
             ui.label(format!("{:?}", state.ui_state.row_selections));
+
+            if let Some(table_id) = state.ui_state.table_id
+                && ui.button("Clear").clicked()
+            {
+                let mut table_state = DeferredTableState::load(ui.ctx(), table_id);
+                table_state.clear_selection();
+                table_state.store(ui.ctx());
+
+                // no action is generated when the state is changed this way
+                state.ui_state.row_selections.clear();
+            }
         });
     });
 
@@ -1032,6 +1032,24 @@ impl DeferredTableDataSource for SpreadsheetSource {
             column_count: columns,
         }
     }
+
+    /// search the displayed values, not the formulas
+    fn search_text(&self, cell_index: CellIndex) -> Option<String> {
+        let value = match self.get_cell_value(cell_index)? {
+            CellValue::Calculated(_formula, FormulaResult::Value(value)) => value,
+            CellValue::Calculated(_formula, FormulaResult::Error(message)) => {
+                return Some(message.clone());
+            }
+            CellValue::Calculated(_formula, FormulaResult::Pending) => return None,
+            CellValue::Value(value) => value,
+        };
+
+        match value {
+            Value::Text(text) => Some(text.clone()),
+            Value::Decimal(decimal) => Some(decimal.to_string()),
+            Value::Empty => None,
+        }
+    }
 }
 
 #[derive(Default)]
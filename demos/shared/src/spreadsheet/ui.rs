@@ -1,8 +1,9 @@
 use crate::spreadsheet::{SpreadsheetRenderer, SpreadsheetSource};
+use egui::text::{CCursor, CCursorRange};
 use egui::{Frame, Response, Ui};
 use egui_deferred_table::{
-    Action, AxisParameters, CellIndex, DeferredTable, DeferredTableDataSource,
-    EditableTableRenderer, EditorState,
+    Action, ApplyTextError, AxisParameters, CellIndex, DeferredTable, DeferredTableDataSource,
+    EditHistory, EditableTableRenderer, EditorState,
 };
 use log::debug;
 
@@ -83,6 +84,9 @@ impl SpreadsheetState {
             .row_parameters(row_params)
             .highlight_hovered_cell()
             .selectable_rows_disabled()
+            .selectable_cells()
+            .selectable_columns()
+            .search_bar()
             .show_and_edit(
                 ui,
                 &mut self.data_source,
@@ -99,7 +103,9 @@ impl Default for SpreadsheetState {
             data_source: SpreadsheetSource::new(),
             renderer: SpreadsheetRenderer::default(),
             editor: SpreadsheetEditor::default(),
-            edit_state: EditorState::default(),
+            // column and row reorders move the data, see `handle_actions`, so only the edits are undone.
+            edit_state: EditorState::default()
+                .with_history(EditHistory::default().ignore_reordering()),
             value: None,
             automatic_recalculation: false,
             column_parameters: None,
@@ -123,6 +129,38 @@ pub fn handle_actions(actions: Vec<Action>, state: &mut SpreadsheetState) {
                     .get_cell_value(cell_index)
                     .map(|value| (cell_index, value.to_editable()));
             }
+            Action::ActiveCellChanged { cell_index } => {
+                state.value = state
+                    .data_source
+                    .get_cell_value(cell_index)
+                    .map(|value| (cell_index, value.to_editable()));
+            }
+            Action::CellDoubleClicked(_)
+            | Action::CellSecondaryClicked { .. }
+            | Action::CellMiddleClicked(_)
+            | Action::HeaderDoubleClicked { .. }
+            | Action::HeaderSecondaryClicked { .. }
+            | Action::HeaderMiddleClicked { .. }
+            | Action::ColumnVisibilityChanged { .. }
+            | Action::ColumnPinChanged { .. }
+            | Action::ColumnGroupReorder { .. }
+            | Action::RowExpansionChanged { .. }
+            | Action::RowGroupExpansionChanged { .. }
+            | Action::CopiedHtml { .. } => {
+                // not used by this example
+            }
+            Action::ColumnWidthChanged { .. } => {
+                // already applied by the table
+            }
+            Action::Pasted { .. } | Action::Undo(_) | Action::Redo(_) => {
+                // the value of the selected cell may have been changed
+                state.value = state.value.take().and_then(|(cell_index, _)| {
+                    state
+                        .data_source
+                        .get_cell_value(cell_index)
+                        .map(|value| (cell_index, value.to_editable()))
+                });
+            }
             Action::ColumnReorder { from, to } => {
                 // we actually want to MOVE the column data itself, not re-order the columns
                 state.data_source.move_column(from, to);
@@ -138,6 +176,32 @@ pub fn handle_actions(actions: Vec<Action>, state: &mut SpreadsheetState) {
                 // row selection currently disabled
                 unreachable!()
             }
+            Action::ColumnSelectionChanged { selection } => {
+                // nothing to do, the table renders the selection
+                debug!("column selection: {:?}", selection);
+            }
+            Action::CellSelectionChanged { selection } => {
+                // nothing to do, the table renders the selection
+                debug!("cell selection: {:?}", selection);
+            }
+            Action::SortChanged {
+                column,
+                direction,
+                spec,
+            } => {
+                let (_, _, _) = (column, direction, spec);
+                // sorting currently disabled
+                unreachable!()
+            }
+            Action::FilterChanged {
+                column,
+                filter,
+                filters,
+            } => {
+                let (_, _, _) = (column, filter, filters);
+                // filtering currently disabled
+                unreachable!()
+            }
         }
     }
 }
@@ -199,6 +263,40 @@ impl EditableTableRenderer<SpreadsheetSource> for SpreadsheetEditor {
         Some((value.clone(), value))
     }
 
+    fn build_item_state_from_text(
+        &self,
+        cell_index: CellIndex,
+        initial_text: &str,
+        source: &mut SpreadsheetSource,
+    ) -> Option<(Self::ItemState, Self::Value)> {
+        // typing replaces the value of the cell
+        let value = source.get_cell_value(cell_index).unwrap().to_editable();
+
+        Some((initial_text.to_string(), value))
+    }
+
+    fn apply_text(
+        &mut self,
+        cell_index: CellIndex,
+        text: &str,
+        source: &mut SpreadsheetSource,
+    ) -> Result<(), ApplyTextError> {
+        // like editing, any text is accepted, invalid formulas are shown as errors
+        source.set_cell_value(&cell_index, text);
+        Ok(())
+    }
+
+    fn revert(
+        &mut self,
+        cell_index: CellIndex,
+        value: Self::Value,
+        source: &mut SpreadsheetSource,
+    ) -> Option<Self::Value> {
+        let replaced = source.get_cell_value(cell_index)?.to_editable();
+        source.set_cell_value(&cell_index, &value);
+        Some(replaced)
+    }
+
     fn on_edit_complete(
         &mut self,
         index: CellIndex,
@@ -209,23 +307,45 @@ impl EditableTableRenderer<SpreadsheetSource> for SpreadsheetEditor {
         source.set_cell_value(&index, &state);
     }
 
+    fn on_edit_cancelled(
+        &mut self,
+        index: CellIndex,
+        _state: Self::ItemState,
+        original_item: Self::Value,
+        source: &mut SpreadsheetSource,
+    ) {
+        // the value is updated while editing, so restore the original value
+        source.set_cell_value(&index, &original_item);
+    }
+
     fn render_cell_editor(
         &self,
         ui: &mut Ui,
         cell_index: &CellIndex,
         state: &mut Self::ItemState,
-        _original_item: &Self::Value,
+        original_item: &Self::Value,
         source: &mut SpreadsheetSource,
     ) {
-        let editor = ui.add(
-            egui::TextEdit::singleline(state)
-                .min_size(ui.available_size())
-                .frame(Frame::NONE),
-        );
+        let mut output = egui::TextEdit::singleline(state)
+            .min_size(ui.available_size())
+            .frame(Frame::NONE)
+            .show(ui);
+        let editor = &output.response.response;
+        let just_opened = !editor.has_focus();
 
-        editor.request_focus();
+        if just_opened {
+            // the editor was just opened, place the caret at the end, e.g. after any typed text.
+            let end = CCursor::new(state.chars().count());
+            output
+                .state
+                .cursor
+                .set_char_range(Some(CCursorRange::one(end)));
+            output.state.store(ui.ctx(), editor.id);
+            editor.request_focus();
+        }
 
-        if editor.changed() {
+        // when the editor was opened by typing, the state already differs from the original value.
+        if editor.changed() || (just_opened && state != original_item) {
             // Note: here we attempt to use the value, regardless of if it's a valid formula, etc.
             //       this gives us a 'live-update' functionality.
             //       we could just do this once `on_edit_complete`, but then the spreadsheet wouldn't change as the user types
@@ -1,11 +1,54 @@
-use crate::CellIndex;
-use std::collections::BTreeSet;
+use crate::{
+    Axis, CellIndex, CellRange, ColumnFilter, HistoryChange, Pin, SortDirection, SortSpec,
+};
+use egui::Pos2;
+use std::collections::{BTreeMap, BTreeSet};
 
 #[derive(Clone, Debug)]
 pub enum Action {
     CellClicked(CellIndex),
 
-    /// Generated when the user drags-and-drops one column onto another.
+    /// Generated when the user double-clicks on a cell, [`Action::CellClicked`] is also generated for both clicks.
+    ///
+    /// See also:
+    /// 1. [`crate::DeferredTable::edit_on_double_click`]
+    CellDoubleClicked(CellIndex),
+
+    /// Generated when the user secondary-clicks (right-clicks) on a cell, or long-presses it on a touch screen.
+    ///
+    /// `pos` is the position of the pointer, e.g. for showing a popup.
+    CellSecondaryClicked {
+        cell_index: CellIndex,
+        pos: Pos2,
+    },
+
+    /// Generated when the user middle-clicks on a cell.
+    CellMiddleClicked(CellIndex),
+
+    /// Generated when the user double-clicks on a column or row header, `index` is the mapped column/row index.
+    HeaderDoubleClicked {
+        axis: Axis,
+        index: usize,
+    },
+
+    /// Generated when the user secondary-clicks (right-clicks) on a column or row header, or long-presses it on a touch
+    /// screen.  `index` is the mapped column/row index.
+    ///
+    /// `pos` is the position of the pointer, e.g. for showing a popup.
+    HeaderSecondaryClicked {
+        axis: Axis,
+        index: usize,
+        pos: Pos2,
+    },
+
+    /// Generated when the user middle-clicks on a column or row header, `index` is the mapped column/row index.
+    HeaderMiddleClicked {
+        axis: Axis,
+        index: usize,
+    },
+
+    /// Generated when the user drags-and-drops one column onto another, or moves a column using the
+    /// [`crate::ColumnChooser`].
     ///
     /// Handle it as follows:
     /// a) updating the column ordering information appropriately.
@@ -20,6 +63,20 @@ pub enum Action {
         to: usize,
     },
 
+    /// Generated when the user drags-and-drops a column group header onto a column header.
+    ///
+    /// `group` is the index of the group in the column groups, `to` is the mapped index of the column it was dropped onto.
+    ///
+    /// Handle it in the same way as [`Action::ColumnReorder`], but moving all the columns of the group.
+    ///
+    /// See also:
+    /// 1. [`crate::DeferredTable::column_groups`]
+    /// 2. [`crate::apply_group_reordering`]
+    ColumnGroupReorder {
+        group: usize,
+        to: usize,
+    },
+
     /// Generated when the user drags-and-drops one row onto another.
     ///
     /// Handle it as follows:
@@ -35,8 +92,188 @@ pub enum Action {
         to: usize,
     },
 
+    /// Generated when the user collapses or expands a row by clicking on its disclosure triangle.
+    ///
+    /// `row` is the mapped row index, `collapsed` contains all the collapsed rows.  The table has already hidden or shown
+    /// the descendants of the row, no handling is required.
+    ///
+    /// See also:
+    /// 1. [`crate::DeferredTableDataSource::tree_row`]
+    /// 2. [`crate::DeferredTableState::set_row_expanded`]
+    RowExpansionChanged {
+        row: usize,
+        expanded: bool,
+        collapsed: BTreeSet<usize>,
+    },
+
+    /// Generated when the user collapses or expands a row group by clicking on its header.
+    ///
+    /// `group` is the key of the group, `collapsed` contains the keys of all the collapsed groups.  The table has already
+    /// hidden or shown the rows of the group, no handling is required.
+    ///
+    /// See also:
+    /// 1. [`crate::DeferredTable::group_rows_by`]
+    /// 2. [`crate::DeferredTableState::set_group_expanded`]
+    RowGroupExpansionChanged {
+        group: String,
+        expanded: bool,
+        collapsed: BTreeSet<String>,
+    },
+
     /// Generated when the user selected or deselects one or more rows.
     RowSelectionChanged {
         selection: BTreeSet<usize>,
     },
+
+    /// Generated when the user selects or deselects one or more columns.
+    ///
+    /// See also:
+    /// 1. [`crate::DeferredTable::selectable_columns`]
+    ColumnSelectionChanged {
+        selection: BTreeSet<usize>,
+    },
+
+    /// Generated when the user selects or deselects one or more cells.
+    ///
+    /// `selection` contains all the selected ranges, using mapped cell indexes, the last range is the most recent.
+    ///
+    /// See also:
+    /// 1. [`crate::DeferredTable::selectable_cells`]
+    /// 2. [`crate::CellRange::indexes`]
+    CellSelectionChanged {
+        selection: Vec<CellRange>,
+    },
+
+    /// Generated when the active cell is changed by clicking on a cell or by using the keyboard.
+    ///
+    /// The arrow keys, Home/End, Ctrl+Home/End and PageUp/PageDown move the active cell through the visible cells, the
+    /// table scrolls to keep the active cell visible.  `cell_index` is the mapped cell index.  When an editor is used,
+    /// the active cell is the [`crate::CellEditState::Pivot`].
+    ///
+    /// See also:
+    /// 1. [`crate::DeferredTable::show_and_edit`]
+    ActiveCellChanged {
+        cell_index: CellIndex,
+    },
+
+    /// Generated when the user clicks on the header of a sortable column.
+    ///
+    /// `column` is the mapped column index, `direction` is `None` when the column is no-longer sorted.
+    /// `spec` contains all the sorted columns, shift+clicking a header adds the column to the existing sorted columns.
+    ///
+    /// Handle it by updating the row ordering, or by sorting the underlying data source.
+    ///
+    /// See also:
+    /// 1. [`crate::DeferredTable::sortable_columns`]
+    /// 2. [`crate::build_sort_ordering`]
+    /// 3. [`crate::build_sort_spec_ordering`]
+    SortChanged {
+        column: usize,
+        direction: Option<SortDirection>,
+        spec: SortSpec,
+    },
+
+    /// Generated when the user changes a filter using the filter popup in a column header.
+    ///
+    /// `column` is the mapped column index, `filter` is `None` when the column is no-longer filtered.
+    /// `filters` contains the filters for all the filtered columns, keyed by mapped column index.
+    ///
+    /// Handle it by updating the rows to filter, or by filtering the underlying data source.
+    ///
+    /// See also:
+    /// 1. [`crate::AxisParameters::filter`]
+    /// 2. [`crate::build_rows_to_filter`]
+    FilterChanged {
+        column: usize,
+        filter: Option<ColumnFilter>,
+        filters: BTreeMap<usize, ColumnFilter>,
+    },
+
+    /// Generated when the user hides a column using the column header menu, or hides or shows a column using the
+    /// [`crate::ColumnChooser`].
+    ///
+    /// `column` is the mapped column index, `hidden` contains all the hidden columns, i.e. the renderer's
+    /// [`crate::DeferredTableRenderer::columns_to_filter`] with the column added or removed.
+    ///
+    /// Handle it by updating the columns to filter.
+    ///
+    /// See also:
+    /// 1. [`crate::DeferredTable::column_header_menu`]
+    /// 2. [`crate::DeferredTable::column_chooser`]
+    ColumnVisibilityChanged {
+        column: usize,
+        visible: bool,
+        hidden: BTreeSet<usize>,
+    },
+
+    /// Generated when the user auto-fits or resets the width of a column using the column header menu.
+    ///
+    /// `column` is the mapped column index, the table has already applied the `width`, no handling is required.
+    ///
+    /// See also:
+    /// 1. [`crate::DeferredTable::column_header_menu`]
+    /// 2. [`crate::DeferredTableState::set_column_widths`]
+    ColumnWidthChanged {
+        column: usize,
+        width: f32,
+    },
+
+    /// Generated when the user pins or unpins a column using the column header menu.
+    ///
+    /// `column` is the mapped column index, `pin` is `None` when the column is unpinned.
+    ///
+    /// Handle it by updating the column's parameters.
+    ///
+    /// See also:
+    /// 1. [`crate::DeferredTable::column_header_menu`]
+    /// 2. [`crate::AxisParameters::pinned`]
+    ColumnPinChanged {
+        column: usize,
+        pin: Option<Pin>,
+    },
+
+    /// Generated when the user copies cells using Ctrl+C and the copy format is [`crate::CopyFormat::Html`].
+    ///
+    /// The table has already copied the cells as tab-separated values text, since egui can only copy text.  Handle it by
+    /// adding `html` to the clipboard as an HTML flavour, e.g. using the platform's clipboard API, or ignore it.
+    ///
+    /// See also:
+    /// 1. [`crate::DeferredTable::copy_format`]
+    CopiedHtml {
+        html: String,
+    },
+
+    /// Generated when the user pastes text from the clipboard into the table, e.g. using Ctrl+V, when editing.
+    ///
+    /// The text is parsed into rows of cell texts which are pasted into the cells starting at the active cell, in
+    /// visible order, see [`crate::CopyFormat::parse`].  Each cell text is applied using
+    /// [`crate::EditableTableRenderer::apply_text`].
+    ///
+    /// `applied` contains the mapped indexes of the changed cells, `rejected` contains the mapped indexes of the cells
+    /// whose text was rejected, with the error message, see [`crate::ApplyTextError`].
+    ///
+    /// See also:
+    /// 1. [`crate::DeferredTable::show_and_edit`]
+    Pasted {
+        applied: Vec<CellIndex>,
+        rejected: Vec<(CellIndex, String)>,
+    },
+
+    /// Generated when the user presses Ctrl+Z and there is a change to undo.
+    ///
+    /// Cells, column widths and row heights have already been restored, handle re-orderings by restoring the ordering,
+    /// see [`HistoryChange`].
+    ///
+    /// See also:
+    /// 1. [`crate::EditHistory`]
+    /// 2. [`crate::EditableTableRenderer::revert`]
+    Undo(HistoryChange),
+
+    /// Generated when the user presses Ctrl+Y or Ctrl+Shift+Z and there is an undone change to redo.
+    ///
+    /// Handle it in the same way as [`Action::Undo`].
+    ///
+    /// See also:
+    /// 1. [`crate::EditHistory`]
+    Redo(HistoryChange),
 }
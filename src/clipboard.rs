@@ -0,0 +1,362 @@
+use crate::CellIndex;
+use std::collections::BTreeSet;
+
+/// The format of the text copied to the clipboard, see [`crate::DeferredTable::copy_format`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum CopyFormat {
+    /// tab-separated values, as used by spreadsheets.
+    #[default]
+    Tsv,
+    /// comma-separated values.
+    Csv,
+    /// an HTML `<table>`, for pasting into documents and emails.
+    ///
+    /// egui can only copy text, so the clipboard text is tab-separated values and the HTML is returned using
+    /// [`crate::Action::CopiedHtml`], to be put on the clipboard as an additional HTML flavour.
+    Html,
+}
+
+impl CopyFormat {
+    /// formats the rows of cell texts.
+    pub fn format(&self, rows: &[Vec<String>]) -> String {
+        match self {
+            CopyFormat::Tsv => Self::format_separated(rows, '\t'),
+            CopyFormat::Csv => Self::format_separated(rows, ','),
+            CopyFormat::Html => {
+                let mut html = String::from("<table>\n");
+                for row in rows {
+                    html.push_str("<tr>");
+                    for text in row {
+                        html.push_str("<td>");
+                        html.push_str(&escape_html(text));
+                        html.push_str("</td>");
+                    }
+                    html.push_str("</tr>\n");
+                }
+                html.push_str("</table>");
+                html
+            }
+        }
+    }
+
+    /// formats the rows of cell texts as the clipboard text, [`CopyFormat::Html`] is copied as tab-separated values.
+    pub(crate) fn format_text(&self, rows: &[Vec<String>]) -> String {
+        match self {
+            CopyFormat::Html => CopyFormat::Tsv.format(rows),
+            _ => self.format(rows),
+        }
+    }
+
+    /// parses text, e.g. pasted from a spreadsheet, into rows of cell texts.
+    ///
+    /// Text containing a tab, outside of quoted texts, is always parsed as tab-separated values, since that is what
+    /// spreadsheets put on the clipboard, otherwise text is parsed as comma-separated values for [`CopyFormat::Csv`] and
+    /// as tab-separated values for the other formats.  Texts that start with a quote are quoted, they may contain
+    /// separators, line breaks and doubled quotes, a trailing line break is ignored.
+    pub fn parse(&self, text: &str) -> Vec<Vec<String>> {
+        let separator = match self {
+            CopyFormat::Csv if !contains_unquoted(text, '\t') => ',',
+            _ => '\t',
+        };
+
+        let mut rows = vec![];
+        let mut row = vec![];
+        let mut field = String::new();
+        let mut quoted = false;
+        let mut characters = text.chars().peekable();
+        while let Some(character) = characters.next() {
+            if quoted {
+                match character {
+                    '"' if characters.peek() == Some(&'"') => {
+                        characters.next();
+                        field.push('"');
+                    }
+                    '"' => quoted = false,
+                    _ => field.push(character),
+                }
+            } else if character == '"' && field.is_empty() {
+                quoted = true;
+            } else if character == separator {
+                row.push(std::mem::take(&mut field));
+            } else if character == '\n' {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            } else if character != '\r' {
+                field.push(character);
+            }
+        }
+        if !field.is_empty() || !row.is_empty() {
+            row.push(field);
+            rows.push(row);
+        }
+        rows
+    }
+
+    /// texts containing the separator, a quote or a line break are quoted, with quotes doubled, which is what
+    /// spreadsheets expect for both TSV and CSV.
+    fn format_separated(rows: &[Vec<String>], separator: char) -> String {
+        rows.iter()
+            .map(|row| {
+                row.iter()
+                    .map(|text| {
+                        if text.contains([separator, '"', '\n', '\r']) {
+                            format!("\"{}\"", text.replace('"', "\"\""))
+                        } else {
+                            text.clone()
+                        }
+                    })
+                    .collect::<Vec<_>>()
+                    .join(&separator.to_string())
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// returns true if the text contains the character outside of quoted texts, as when parsing, a quote only starts a quoted
+/// text at the start of a text, i.e. at the start of the text or after a separator or a line break.
+fn contains_unquoted(text: &str, character: char) -> bool {
+    let mut quoted = false;
+    let mut text_start = true;
+    let mut characters = text.chars().peekable();
+    while let Some(it) = characters.next() {
+        if quoted {
+            if it == '"' && characters.next_if_eq(&'"').is_none() {
+                quoted = false;
+            }
+            text_start = false;
+            continue;
+        }
+        if it == character {
+            return true;
+        }
+        quoted = it == '"' && text_start;
+        text_start = matches!(it, '\t' | ',' | '\n');
+    }
+    false
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for character in text.chars() {
+        match character {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\n' => escaped.push_str("<br>"),
+            _ => escaped.push(character),
+        }
+    }
+    escaped
+}
+
+/// Returns the cells to copy, using mapped indexes, as rows of cells in visible order.
+///
+/// `selections` contains the selected rows and columns of each selected range, `visible_rows` and `visible_columns` are
+/// the mapped indexes in visible order, excluding filtered indexes.  Only the rows and columns with selected cells are
+/// included, cells in those rows and columns which are not selected, e.g. when there are multiple ranges, are `None`.
+pub(crate) fn copied_cells(
+    selections: &[(BTreeSet<usize>, BTreeSet<usize>)],
+    visible_rows: &[usize],
+    visible_columns: &[usize],
+) -> Vec<Vec<Option<CellIndex>>> {
+    let is_selected = |row: usize, column: usize| {
+        selections
+            .iter()
+            .any(|(rows, columns)| rows.contains(&row) && columns.contains(&column))
+    };
+
+    let columns = visible_columns
+        .iter()
+        .copied()
+        .filter(|column| {
+            selections
+                .iter()
+                .any(|(_, columns)| columns.contains(column))
+        })
+        .collect::<Vec<_>>();
+
+    visible_rows
+        .iter()
+        .copied()
+        .filter(|row| selections.iter().any(|(rows, _)| rows.contains(row)))
+        .map(|row| {
+            columns
+                .iter()
+                .map(|&column| is_selected(row, column).then_some(CellIndex { row, column }))
+                .collect()
+        })
+        .collect()
+}
+
+/// Returns the cells to paste the rows of texts into, using mapped indexes, and their texts.
+///
+/// The first text is pasted into the `pivot` cell, the other texts are pasted into the cells at the same visible offset
+/// from the pivot cell, texts beyond the last visible row or column are ignored.  Nothing is pasted if the pivot cell
+/// is not visible.
+pub(crate) fn pasted_cells(
+    pivot: CellIndex,
+    rows: Vec<Vec<String>>,
+    visible_rows: &[usize],
+    visible_columns: &[usize],
+) -> Vec<(CellIndex, String)> {
+    let (Some(first_row), Some(first_column)) = (
+        visible_rows.iter().position(|row| *row == pivot.row),
+        visible_columns
+            .iter()
+            .position(|column| *column == pivot.column),
+    ) else {
+        return vec![];
+    };
+
+    visible_rows[first_row..]
+        .iter()
+        .zip(rows)
+        .flat_map(|(&row, texts)| {
+            visible_columns[first_column..]
+                .iter()
+                .zip(texts)
+                .map(move |(&column, text)| (CellIndex { row, column }, text))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod clipboard_tests {
+    use crate::CellIndex;
+    use crate::clipboard::{CopyFormat, copied_cells, pasted_cells};
+    use rstest::rstest;
+    use std::collections::BTreeSet;
+
+    fn cell(row: usize, column: usize) -> Option<CellIndex> {
+        Some(CellIndex { row, column })
+    }
+
+    fn rows() -> Vec<Vec<String>> {
+        vec![
+            vec!["R1".to_string(), "10k, 1%".to_string()],
+            vec!["C1".to_string(), "\"100n\"\t<50V>".to_string()],
+        ]
+    }
+
+    #[rstest]
+    #[case(CopyFormat::Tsv, "R1\t10k, 1%\nC1\t\"\"\"100n\"\"\t<50V>\"")]
+    #[case(CopyFormat::Csv, "R1,\"10k, 1%\"\nC1,\"\"\"100n\"\"\t<50V>\"")]
+    #[case(
+        CopyFormat::Html,
+        "<table>\n<tr><td>R1</td><td>10k, 1%</td></tr>\n<tr><td>C1</td><td>&quot;100n&quot;\t&lt;50V&gt;</td></tr>\n</table>"
+    )]
+    fn test_format(#[case] format: CopyFormat, #[case] expected: &str) {
+        assert_eq!(format.format(&rows()), expected);
+    }
+
+    #[rstest]
+    #[case(CopyFormat::Tsv, "R1\t10k, 1%\nC1\t\"\"\"100n\"\"\t<50V>\"")]
+    #[case(CopyFormat::Csv, "R1,\"10k, 1%\"\nC1,\"\"\"100n\"\"\t<50V>\"")]
+    #[case(CopyFormat::Html, "R1\t10k, 1%\nC1\t\"\"\"100n\"\"\t<50V>\"")]
+    fn test_format_text(#[case] format: CopyFormat, #[case] expected: &str) {
+        assert_eq!(format.format_text(&rows()), expected);
+    }
+
+    #[test]
+    fn test_copied_cells_in_visible_order() {
+        // given
+        // a range covering rows 0 and 2, and columns 1 and 3, where row 1 is filtered
+        let selections = [(BTreeSet::from([0, 1, 2]), BTreeSet::from([1, 3]))];
+
+        // when
+        let cells = copied_cells(&selections, &[2, 0, 3], &[3, 0, 1]);
+
+        // then
+        assert_eq!(
+            cells,
+            vec![vec![cell(2, 3), cell(2, 1)], vec![cell(0, 3), cell(0, 1)]]
+        );
+    }
+
+    #[test]
+    fn test_copied_cells_with_multiple_ranges() {
+        // given
+        let selections = [
+            (BTreeSet::from([0]), BTreeSet::from([0])),
+            (BTreeSet::from([1]), BTreeSet::from([1])),
+        ];
+
+        // when
+        let cells = copied_cells(&selections, &[0, 1, 2], &[0, 1, 2]);
+
+        // then
+        assert_eq!(cells, vec![vec![cell(0, 0), None], vec![None, cell(1, 1)]]);
+    }
+
+    #[rstest]
+    #[case(CopyFormat::Tsv, "R1\t10k\nC1\t100n\n", vec![vec!["R1", "10k"], vec!["C1", "100n"]])]
+    #[case(CopyFormat::Tsv, "R1\t10k, 1%\r\nC1\t", vec![vec!["R1", "10k, 1%"], vec!["C1", ""]])]
+    #[case(CopyFormat::Tsv, "R1,10k", vec![vec!["R1,10k"]])]
+    #[case(CopyFormat::Csv, "R1,\"10k, 1%\"\nC1,\"\"\"100n\"\"\"", vec![vec!["R1", "10k, 1%"], vec!["C1", "\"100n\""]])]
+    #[case(CopyFormat::Csv, "R1\t10k, 1%", vec![vec!["R1", "10k, 1%"]])]
+    // a quote inside a text doesn't start a quoted text
+    #[case(CopyFormat::Csv, "5\" LCD\tX", vec![vec!["5\" LCD", "X"]])]
+    #[case(CopyFormat::Csv, "R1,\"10k\t1%\"", vec![vec!["R1", "10k\t1%"]])]
+    #[case(CopyFormat::Tsv, "\"line 1\nline 2\"\tR1", vec![vec!["line 1\nline 2", "R1"]])]
+    #[case(CopyFormat::Tsv, "", vec![])]
+    fn test_parse(
+        #[case] format: CopyFormat,
+        #[case] text: &str,
+        #[case] expected: Vec<Vec<&str>>,
+    ) {
+        assert_eq!(format.parse(text), expected);
+    }
+
+    #[rstest]
+    #[case(CopyFormat::Tsv)]
+    #[case(CopyFormat::Csv)]
+    fn test_parse_formatted(#[case] format: CopyFormat) {
+        let rows = rows();
+
+        assert_eq!(format.parse(&format.format(&rows)), rows);
+    }
+
+    #[test]
+    fn test_pasted_cells_in_visible_order() {
+        // given
+        let rows = vec![
+            vec!["a".to_string(), "b".to_string(), "ignored".to_string()],
+            vec!["c".to_string(), "d".to_string()],
+            vec!["ignored".to_string()],
+        ];
+
+        // when
+        // the pivot is the second visible row and column
+        let cells = pasted_cells(
+            CellIndex { row: 0, column: 3 },
+            rows,
+            &[2, 0, 3],
+            &[1, 3, 0],
+        );
+
+        // then
+        assert_eq!(
+            cells,
+            vec![
+                (CellIndex { row: 0, column: 3 }, "a".to_string()),
+                (CellIndex { row: 0, column: 0 }, "b".to_string()),
+                (CellIndex { row: 3, column: 3 }, "c".to_string()),
+                (CellIndex { row: 3, column: 0 }, "d".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_pasted_cells_with_filtered_pivot() {
+        let cells = pasted_cells(
+            CellIndex { row: 1, column: 0 },
+            vec![vec!["a".to_string()]],
+            &[0, 2],
+            &[0],
+        );
+
+        assert!(cells.is_empty());
+    }
+}
@@ -0,0 +1,235 @@
+use crate::header_menu::hidden_columns;
+use crate::ordering::visible_indexes;
+use crate::{Action, AxisParameters, DefaultStrings, DeferredTableStrings};
+use egui::{Button, Checkbox, ScrollArea, Ui};
+
+/// A list of checkboxes to hide and show columns, with buttons to move each column up or down the column ordering.
+///
+/// The columns are listed in visible order, including the hidden columns, and are labelled using
+/// [`AxisParameters::name`], or the column number when there is no name.
+///
+/// The table shows it when the corner cell is secondary-clicked (right-clicked), and in the column header menu, see
+/// [`crate::DeferredTable::column_chooser`].  It can also be shown elsewhere, e.g. in a side panel, the returned actions
+/// should then be handled in the same way as the actions returned by the table.
+///
+/// ```text
+/// let actions = ColumnChooser::new(column_count, renderer.column_ordering(), renderer.columns_to_filter())
+///     .column_parameters(&column_parameters)
+///     .show(ui);
+/// ```
+///
+/// See also:
+/// 1. [`Action::ColumnVisibilityChanged`]
+/// 2. [`Action::ColumnReorder`]
+pub struct ColumnChooser<'a> {
+    column_count: usize,
+    column_ordering: &'a [usize],
+    columns_to_filter: Option<&'a [usize]>,
+    column_parameters: Option<&'a Vec<AxisParameters>>,
+    zero_based_headers: bool,
+    strings: &'a dyn DeferredTableStrings,
+}
+
+impl<'a> ColumnChooser<'a> {
+    /// `column_ordering` and `columns_to_filter` are as returned by the renderer, see
+    /// [`crate::DeferredTableRenderer::column_ordering`] and [`crate::DeferredTableRenderer::columns_to_filter`].
+    pub fn new(
+        column_count: usize,
+        column_ordering: Option<&'a [usize]>,
+        columns_to_filter: Option<&'a [usize]>,
+    ) -> Self {
+        Self {
+            column_count,
+            column_ordering: column_ordering.unwrap_or_default(),
+            columns_to_filter,
+            column_parameters: None,
+            zero_based_headers: false,
+            strings: &DefaultStrings,
+        }
+    }
+
+    pub fn column_parameters(mut self, column_parameters: &'a Vec<AxisParameters>) -> Self {
+        self.column_parameters = Some(column_parameters);
+        self
+    }
+
+    /// default: one-based column numbers, used for columns without a name.
+    pub fn zero_based_headers(mut self) -> Self {
+        self.zero_based_headers = true;
+        self
+    }
+
+    /// default: [`DefaultStrings`], English
+    pub fn strings(mut self, strings: &'a dyn DeferredTableStrings) -> Self {
+        self.strings = strings;
+        self
+    }
+
+    pub fn show(self, ui: &mut Ui) -> Vec<Action> {
+        let mut actions = vec![];
+
+        let columns =
+            visible_indexes(self.column_count, self.column_ordering, None).collect::<Vec<_>>();
+        let visible_count = visible_indexes(
+            self.column_count,
+            self.column_ordering,
+            self.columns_to_filter,
+        )
+        .count();
+
+        ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+            for (position, column) in columns.iter().copied().enumerate() {
+                ui.horizontal(|ui| {
+                    for (label, hover_text, up) in [
+                        ("⏶", self.strings.move_column_up(), true),
+                        ("⏷", self.strings.move_column_down(), false),
+                    ] {
+                        let moved = moved_column(&columns, position, up);
+                        if ui
+                            .add_enabled(moved.is_some(), Button::new(label))
+                            .on_hover_text(hover_text)
+                            .clicked()
+                            && let Some((from, to)) = moved
+                        {
+                            actions.push(Action::ColumnReorder { from, to });
+                        }
+                    }
+
+                    let mut visible = !self
+                        .columns_to_filter
+                        .is_some_and(|filter| filter.contains(&column));
+                    // at least one column must remain visible
+                    let enabled = !visible || visible_count > 1;
+                    if ui
+                        .add_enabled(enabled, Checkbox::new(&mut visible, self.label(column)))
+                        .changed()
+                    {
+                        actions.push(Action::ColumnVisibilityChanged {
+                            column,
+                            visible,
+                            hidden: hidden_columns(self.columns_to_filter, column, visible),
+                        });
+                    }
+                });
+            }
+        });
+
+        actions
+    }
+
+    fn label(&self, column: usize) -> String {
+        self.column_parameters
+            .and_then(|it| it.get(column))
+            .and_then(|it| it.name.clone())
+            .unwrap_or_else(|| match self.zero_based_headers {
+                true => column.to_string(),
+                false => (column + 1).to_string(),
+            })
+    }
+}
+
+/// returns the `from` and `to` of the [`Action::ColumnReorder`] that moves the column at `position` up or down, `None`
+/// if the column is already the first or last column.
+fn moved_column(columns: &[usize], position: usize, up: bool) -> Option<(usize, usize)> {
+    let other_position = match up {
+        true => position.checked_sub(1)?,
+        false => position + 1,
+    };
+    Some((columns[position], *columns.get(other_position)?))
+}
+
+#[cfg(test)]
+mod column_chooser_tests {
+    use crate::column_chooser::moved_column;
+    use crate::{Action, ColumnChooser, apply_reordering};
+    use egui::{Context, Event, LayerId, Modifiers, PointerButton, RawInput};
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(0, true, None)]
+    #[case(0, false, Some((2, 0)))]
+    #[case(1, true, Some((0, 2)))]
+    #[case(2, true, Some((1, 0)))]
+    #[case(2, false, None)]
+    fn test_moved_column(
+        #[case] position: usize,
+        #[case] up: bool,
+        #[case] expected: Option<(usize, usize)>,
+    ) {
+        assert_eq!(moved_column(&[2, 0, 1], position, up), expected);
+    }
+
+    #[rstest]
+    #[case(1, true, vec![0, 2, 1])]
+    #[case(1, false, vec![2, 1, 0])]
+    fn test_moved_column_with_apply_reordering(
+        #[case] position: usize,
+        #[case] up: bool,
+        #[case] expected: Vec<usize>,
+    ) {
+        let mut ordering = Some(vec![2, 0, 1]);
+        let (from, to) = moved_column(&[2, 0, 1], position, up).unwrap();
+
+        apply_reordering(&mut ordering, from, to);
+
+        assert_eq!(ordering, Some(expected));
+    }
+
+    #[test]
+    fn test_show_column() {
+        // given
+        let ctx = Context::default();
+        let show = |events: Vec<Event>| {
+            let mut actions: Vec<Action> = vec![];
+            let _ = ctx.run_ui(
+                RawInput {
+                    events,
+                    ..Default::default()
+                },
+                |ui| {
+                    actions.extend(ColumnChooser::new(3, Some(&[2, 0, 1]), Some(&[0])).show(ui));
+                },
+            );
+            actions
+        };
+        show(vec![]);
+
+        // each row has the move up and move down buttons, then the checkbox
+        let mut clickable = ctx.viewport(|viewport| {
+            viewport
+                .prev_pass
+                .widgets
+                .get_layer(LayerId::background())
+                .filter(|widget| widget.sense.senses_click())
+                .map(|widget| widget.rect)
+                .collect::<Vec<_>>()
+        });
+        clickable.sort_by(|a, b| (a.min.y, a.min.x).partial_cmp(&(b.min.y, b.min.x)).unwrap());
+        assert_eq!(clickable.len(), 9);
+        // the second row is column 0, which is hidden
+        let checkbox = clickable[5].center();
+
+        // when
+        let mut actions = show(vec![
+            Event::PointerMoved(checkbox),
+            Event::PointerButton {
+                pos: checkbox,
+                button: PointerButton::Primary,
+                pressed: true,
+                modifiers: Modifiers::NONE,
+            },
+        ]);
+        actions.extend(show(vec![Event::PointerButton {
+            pos: checkbox,
+            button: PointerButton::Primary,
+            pressed: false,
+            modifiers: Modifiers::NONE,
+        }]));
+
+        // then
+        assert!(matches!(
+            actions.as_slice(),
+            [Action::ColumnVisibilityChanged { column: 0, visible: true, hidden }] if hidden.is_empty()
+        ));
+    }
+}
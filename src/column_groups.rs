@@ -0,0 +1,134 @@
+use crate::Pin;
+use std::ops::Range;
+
+/// A header band shown above the column headers, spanning a group of columns, e.g. "Electrical" spanning the
+/// "Voltage", "Current" and "Power" columns.
+///
+/// Groups are shown in extra header rows above the column headers, one row per level, level 0 is directly above the
+/// column headers and higher levels are above that.  A group is split into several bands when its columns are not
+/// adjacent, e.g. after re-ordering the columns, or when some of its columns are pinned.
+///
+/// Dragging a group header onto a column header moves all the columns of the group, see
+/// [`crate::Action::ColumnGroupReorder`].
+///
+/// See also:
+/// 1. [`crate::DeferredTable::column_groups`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnGroup {
+    pub label: String,
+    /// the mapped column indexes of the columns in the group.
+    pub columns: Vec<usize>,
+    pub level: usize,
+}
+
+impl ColumnGroup {
+    pub fn new(label: impl Into<String>, columns: impl Into<Vec<usize>>) -> Self {
+        Self {
+            label: label.into(),
+            columns: columns.into(),
+            level: 0,
+        }
+    }
+
+    /// the header row the group is shown in, level 0 is directly above the column headers.
+    ///
+    /// default: 0
+    pub fn level(mut self, level: usize) -> Self {
+        self.level = level;
+        self
+    }
+}
+
+/// the number of header rows needed to show the groups.
+pub(crate) fn level_count(groups: &[ColumnGroup]) -> usize {
+    groups
+        .iter()
+        .map(|group| group.level + 1)
+        .max()
+        .unwrap_or(0)
+}
+
+/// returns the runs of adjacent columns of each group at the level, as the index of the group and the range of
+/// `columns` the run spans.
+///
+/// `columns` contains the mapped index and pin of each shown column, in visible order, runs are not continued across
+/// columns with different pins since the pinned bands are not adjacent to the scrolled columns.
+pub(crate) fn group_spans(
+    groups: &[ColumnGroup],
+    level: usize,
+    columns: &[(usize, Option<Pin>)],
+) -> Vec<(usize, Range<usize>)> {
+    let group_of = |column: usize| {
+        groups
+            .iter()
+            .position(|group| group.level == level && group.columns.contains(&column))
+    };
+
+    let mut spans: Vec<(usize, Range<usize>)> = vec![];
+    for (position, (column, pin)) in columns.iter().enumerate() {
+        let Some(group) = group_of(*column) else {
+            continue;
+        };
+        match spans.last_mut() {
+            Some((last_group, range))
+                if *last_group == group
+                    && range.end == position
+                    && columns[position - 1].1 == *pin =>
+            {
+                range.end = position + 1;
+            }
+            _ => spans.push((group, position..position + 1)),
+        }
+    }
+    spans
+}
+
+#[cfg(test)]
+mod column_group_tests {
+    use crate::Pin;
+    use crate::column_groups::{ColumnGroup, group_spans, level_count};
+    use rstest::rstest;
+
+    fn groups() -> Vec<ColumnGroup> {
+        vec![
+            ColumnGroup::new("Electrical", [1, 2, 3]),
+            ColumnGroup::new("Mechanical", [4, 5]),
+            ColumnGroup::new("Measurements", [1, 2, 3, 4, 5]).level(1),
+        ]
+    }
+
+    #[test]
+    fn test_level_count() {
+        assert_eq!(level_count(&[]), 0);
+        assert_eq!(level_count(&groups()), 2);
+    }
+
+    #[rstest]
+    #[case(0, &[0, 1, 2, 3, 4, 5, 6], vec![(0, 1..4), (1, 4..6)])]
+    #[case(1, &[0, 1, 2, 3, 4, 5, 6], vec![(2, 1..6)])]
+    // re-ordering a column out of the group splits it
+    #[case(0, &[0, 1, 6, 2, 3, 4, 5], vec![(0, 1..2), (0, 3..5), (1, 5..7)])]
+    // hidden columns don't split the group
+    #[case(0, &[0, 1, 3, 5], vec![(0, 1..3), (1, 3..4)])]
+    #[case(0, &[], vec![])]
+    fn test_group_spans(
+        #[case] level: usize,
+        #[case] columns: &[usize],
+        #[case] expected: Vec<(usize, std::ops::Range<usize>)>,
+    ) {
+        let columns = columns
+            .iter()
+            .map(|column| (*column, None))
+            .collect::<Vec<_>>();
+        assert_eq!(group_spans(&groups(), level, &columns), expected);
+    }
+
+    #[test]
+    fn test_group_spans_split_by_pin() {
+        let columns = [(1, Some(Pin::Start)), (2, None), (3, None)];
+        assert_eq!(
+            group_spans(&groups(), 0, &columns),
+            vec![(0, 0..1), (0, 1..3)]
+        );
+    }
+}
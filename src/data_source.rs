@@ -1,4 +1,4 @@
-use crate::TableDimensions;
+use crate::{CellIndex, CellSpan, TableDimensions, TreeRow};
 
 pub trait DeferredTableDataSource {
     /// called once per frame, before any other methods are used.
@@ -7,4 +7,94 @@ pub trait DeferredTableDataSource {
     fn finalize(&mut self) {}
 
     fn get_dimensions(&self) -> TableDimensions;
+
+    /// return the text of the cell to be used by the search bar, see [`crate::DeferredTable::search_bar`].
+    ///
+    /// called for every cell of the rows and columns that are not filtered, every frame while there is a query, or only
+    /// when the query, the [`Self::data_revision`], the dimensions, the ordering or the filtering change, if the data
+    /// revision is implemented.  Cells that return `None` never match.
+    fn search_text(&self, _cell_index: CellIndex) -> Option<String> {
+        None
+    }
+
+    /// return true if some cells span several rows and/or columns, see [`Self::cell_span`].  Called once per frame.  The
+    /// default is `false`, i.e. [`Self::cell_span`] is never called.
+    fn has_cell_spans(&self) -> bool {
+        false
+    }
+
+    /// return the span covering the cell, for cells that are merged with the cells below and/or to the right of them,
+    /// e.g. section titles, both for the cell at the origin of the span and for the cells it covers.
+    ///
+    /// The span covers the following rows/columns in visible order, filtered rows/columns are skipped and not counted, so
+    /// re-ordering or filtering rows/columns changes the covered cells.  The origin is rendered across the spanned area
+    /// and the covered cells are not rendered.  Spans end at the edges of pinned columns/rows, and are not shown if the
+    /// origin is filtered.  Spans should not overlap.
+    ///
+    /// called for the visible cells that are not covered by another span, every frame, so it should be cheap, and only
+    /// if [`Self::has_cell_spans`] returns `true`.  The default is `None`, i.e. no span, a span of one row and one column
+    /// is the same as no span.
+    fn cell_span(&self, _cell_index: CellIndex) -> Option<CellSpan> {
+        None
+    }
+
+    /// return the position of the row at the (mapped) row index in a tree of rows, for showing the rows as a tree.
+    ///
+    /// The first visible column is indented by the depth of the row, rows with children show a disclosure triangle,
+    /// clicking it collapses or expands the row, see [`crate::Action::RowExpansionChanged`].  The descendants of
+    /// collapsed rows are filtered, in addition to [`crate::DeferredTableRenderer::rows_to_filter`].  The rows are not
+    /// re-ordered, child rows should follow their parent in the row ordering.
+    ///
+    /// called for the visible rows, every frame, and for every row while any row is collapsed, every frame, or only when
+    /// the collapsed rows, the [`Self::data_revision`], the dimensions or the rows to filter change, if the data revision
+    /// is implemented, so it should be cheap.  The default is `None`, i.e. not a tree.
+    fn tree_row(&self, _index: usize) -> Option<TreeRow> {
+        None
+    }
+
+    /// return true to identify the rows using [`Self::row_key`].  The default is `false`, i.e. the rows are identified by
+    /// their index.
+    fn has_row_keys(&self) -> bool {
+        false
+    }
+
+    /// return a key that identifies the row at the (mapped) row index, keys must be unique, see [`Self::has_row_keys`].
+    ///
+    /// The table keeps the row selection, row heights and edit state with the same row when rows are inserted or removed,
+    /// e.g. inserting a row at the start moves the selection down, instead of selecting the new row.  Rows whose keys
+    /// no-longer exist are deselected.
+    ///
+    /// called for every row, when the dimensions or the [`Self::keys_revision`] change.  The default is the row index.
+    fn row_key(&self, index: usize) -> u64 {
+        index as u64
+    }
+
+    /// return true to identify the columns using [`Self::column_key`].  The default is `false`.
+    fn has_column_keys(&self) -> bool {
+        false
+    }
+
+    /// return a key that identifies the column at the (mapped) column index, keys must be unique.
+    ///
+    /// Same as [`Self::row_key`], but for the column selection, column widths, sorting and filters.
+    fn column_key(&self, index: usize) -> u64 {
+        index as u64
+    }
+
+    /// return a value that changes whenever rows/columns are inserted, removed or moved without changing the number of
+    /// rows/columns, so that the keys are fetched again.  The default is `0`, i.e. the keys only change when the
+    /// dimensions change.
+    fn keys_revision(&self) -> u64 {
+        0
+    }
+
+    /// return a value that changes whenever the data changes, e.g. a counter that is incremented by each change, so that
+    /// the values found using the data source are kept until the data changes, e.g. the matches of the search bar, see
+    /// [`Self::search_text`], the descendants of the collapsed rows, see [`Self::tree_row`], and the row groups, see
+    /// [`crate::DeferredTable::group_rows_by`].
+    ///
+    /// The default is `None`, i.e. changes are not tracked, so the values are found again every frame.
+    fn data_revision(&self) -> Option<u64> {
+        None
+    }
 }
@@ -1,3 +1,19 @@
+/// Identifies the column or row headers, see [`crate::Action::HeaderDoubleClicked`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Axis {
+    Column,
+    Row,
+}
+
+/// Where a pinned column/row is kept, see [`crate::Action::ColumnPinChanged`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Pin {
+    /// the left for columns, the top for rows.
+    Start,
+    /// the right for columns, the bottom for rows.
+    End,
+}
+
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct TableDimensions {
     pub row_count: usize,
@@ -1,5 +1,6 @@
-use crate::CellIndex;
+use crate::{CellIndex, EditHistory};
 use egui::Ui;
+use std::fmt::Display;
 
 ///
 /// Editable table support
@@ -17,6 +18,25 @@ pub trait ApplyChange<T, E> {
     fn apply_change(&mut self, value: T) -> Result<(), E>;
 }
 
+/// The reason the text pasted into a cell was rejected, see [`EditableTableRenderer::apply_text`].
+///
+/// The error is not generic, so that editors which don't support pasting don't need to declare an error type, use
+/// [`ApplyTextError::rejected`] for the errors of an [`ApplyChange`] implementation.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ApplyTextError {
+    /// the cell does not support pasting, the message is [`crate::DeferredTableStrings::paste_not_supported`].
+    NotSupported,
+    /// the text is invalid for the cell, e.g. it could not be parsed, with a message for the user.
+    Rejected(String),
+}
+
+impl ApplyTextError {
+    /// rejects the text with the error as the message, e.g. `source.apply_change(value).map_err(ApplyTextError::rejected)`.
+    pub fn rejected(error: impl Display) -> Self {
+        Self::Rejected(error.to_string())
+    }
+}
+
 /// Implement this to enable data source editing support.
 pub trait EditableTableRenderer<DataSource> {
     /// Usually a type containing the data for a single row.
@@ -34,6 +54,57 @@ pub trait EditableTableRenderer<DataSource> {
         source: &mut DataSource,
     ) -> Option<(Self::ItemState, Self::Value)>;
 
+    /// Called when the user starts typing while a cell is the pivot, `initial_text` is the typed text.
+    ///
+    /// Return None to ignore the typed text, or a tuple containing the ItemState and the original value.  Usually the
+    /// ItemState replaces the value of the cell with the typed text and the editor should show the caret after it.
+    ///
+    /// The default implementation returns None.
+    fn build_item_state_from_text(
+        &self,
+        _cell_index: CellIndex,
+        _initial_text: &str,
+        _source: &mut DataSource,
+    ) -> Option<(Self::ItemState, Self::Value)> {
+        None
+    }
+
+    /// Called for each cell when the user pastes text from the clipboard, e.g. using Ctrl+V, `text` is the text for the
+    /// cell.
+    ///
+    /// Return an error to reject the text, e.g. if it's invalid for the cell, the error message is returned to the
+    /// application in [`crate::Action::Pasted`].  Implementations usually parse the text and modify the data source
+    /// directly, e.g. via an [`ApplyChange`] implementation, whose errors can be converted using
+    /// [`ApplyTextError::rejected`].
+    ///
+    /// The default implementation returns [`ApplyTextError::NotSupported`], i.e. pasting is not supported.
+    fn apply_text(
+        &mut self,
+        _cell_index: CellIndex,
+        _text: &str,
+        _source: &mut DataSource,
+    ) -> Result<(), ApplyTextError> {
+        Err(ApplyTextError::NotSupported)
+    }
+
+    /// Called to undo or redo a change to a cell recorded by an [`EditHistory`], `value` is the value to restore, i.e.
+    /// the original item of a completed edit, the value returned by `build_item_state` before pasting, or a value
+    /// previously returned by this method.
+    ///
+    /// Return the value that was replaced, so that the revert can itself be reverted, or None if the cell cannot be
+    /// reverted.  If none of the cells of a change are reverted, the change stays in the history and no
+    /// [`crate::Action::Undo`] or [`crate::Action::Redo`] is returned.
+    ///
+    /// The default implementation returns None, i.e. reverting is not supported.
+    fn revert(
+        &mut self,
+        _cell_index: CellIndex,
+        _value: Self::Value,
+        _source: &mut DataSource,
+    ) -> Option<Self::Value> {
+        None
+    }
+
     /// Called when the cell is no-longer being edited.
     ///
     /// Implementations usually modify the data source directly, or build and send a command that will change
@@ -46,6 +117,18 @@ pub trait EditableTableRenderer<DataSource> {
         source: &mut DataSource,
     );
 
+    /// Called when the user cancels the edit, e.g. by pressing Escape, `on_edit_complete` is not called.
+    ///
+    /// The default implementation does nothing, the item state is dropped.
+    fn on_edit_cancelled(
+        &mut self,
+        _cell_index: CellIndex,
+        _state: Self::ItemState,
+        _original_item: Self::Value,
+        _source: &mut DataSource,
+    ) {
+    }
+
     /// item state is what the editor should actually edit
     /// original item is supplied so that editor can show differences indicators when state has changed
     /// data source is supplied in case it's needed
@@ -62,11 +145,25 @@ pub trait EditableTableRenderer<DataSource> {
 #[derive(Debug, Clone)]
 pub struct EditorState<IS, V> {
     pub state: Option<CellEditState<IS, V>>,
+    pub history: Option<EditHistory<V>>,
 }
 
 impl<IS, V> Default for EditorState<IS, V> {
     fn default() -> Self {
-        Self { state: None }
+        Self {
+            state: None,
+            history: None,
+        }
+    }
+}
+
+impl<IS, V> EditorState<IS, V> {
+    /// enables undo/redo using Ctrl+Z and Ctrl+Y.
+    ///
+    /// default: no history
+    pub fn with_history(mut self, history: EditHistory<V>) -> Self {
+        self.history = Some(history);
+        self
     }
 }
 
@@ -0,0 +1,321 @@
+use crate::CellIndex;
+use regex::{Regex, RegexBuilder};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Specifies the kind of filter the user can apply to a column, via the filter popup in the column header.
+///
+/// See [`crate::AxisParameters::filter`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum FilterKind {
+    /// contains/equals/regex text matching
+    Text,
+    /// an inclusive min/max range
+    Number,
+    /// a checkbox list of the values, the values are supplied by the application.
+    Values(Vec<String>),
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "persistence", derive(serde::Deserialize, serde::Serialize))]
+pub enum TextFilterOperator {
+    #[default]
+    Contains,
+    Equals,
+    Regex,
+}
+
+/// A filter for a single column.
+///
+/// See also:
+/// 1. [`crate::Action::FilterChanged`]
+/// 2. [`build_rows_to_filter`]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "persistence", derive(serde::Deserialize, serde::Serialize))]
+pub enum ColumnFilter {
+    Text {
+        operator: TextFilterOperator,
+        text: String,
+        case_sensitive: bool,
+    },
+    /// inclusive range, `None` for an unbounded min/max.
+    Range { min: Option<f64>, max: Option<f64> },
+    /// the values to show, values not in the set are filtered.
+    Values(BTreeSet<String>),
+}
+
+impl ColumnFilter {
+    /// returns true if the value should be shown.
+    ///
+    /// Note: a regex is compiled on each call, use [`build_rows_to_filter`] when checking many values.
+    pub fn matches(&self, value: &FilterValue) -> bool {
+        ColumnMatcher::new(self).matches(value)
+    }
+
+    /// returns an error message if the filter cannot be used, e.g. an invalid regex.
+    ///
+    /// Filters that cannot be used do not filter any rows.
+    pub fn error(&self) -> Option<String> {
+        match self {
+            ColumnFilter::Text {
+                operator: TextFilterOperator::Regex,
+                text,
+                case_sensitive,
+            } => build_regex(text, *case_sensitive)
+                .err()
+                .map(|error| error.to_string()),
+            _ => None,
+        }
+    }
+}
+
+/// The value of a cell, used when evaluating filters.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FilterValue<'a> {
+    Text(Cow<'a, str>),
+    Number(f64),
+    Empty,
+}
+
+impl FilterValue<'_> {
+    fn as_text(&self) -> Cow<'_, str> {
+        match self {
+            FilterValue::Text(text) => Cow::Borrowed(text.as_ref()),
+            FilterValue::Number(number) => Cow::Owned(number.to_string()),
+            FilterValue::Empty => Cow::Borrowed(""),
+        }
+    }
+
+    fn as_number(&self) -> Option<f64> {
+        match self {
+            FilterValue::Text(text) => text.trim().parse::<f64>().ok(),
+            FilterValue::Number(number) => Some(*number),
+            FilterValue::Empty => None,
+        }
+    }
+}
+
+impl<'a> From<&'a str> for FilterValue<'a> {
+    fn from(value: &'a str) -> Self {
+        FilterValue::Text(Cow::Borrowed(value))
+    }
+}
+
+impl From<String> for FilterValue<'_> {
+    fn from(value: String) -> Self {
+        FilterValue::Text(Cow::Owned(value))
+    }
+}
+
+impl<'a, T: Into<FilterValue<'a>>> From<Option<T>> for FilterValue<'a> {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(FilterValue::Empty, Into::into)
+    }
+}
+
+macro_rules! impl_filter_value_for_number {
+    ($($T:ty),*) => {
+        $(
+            impl From<$T> for FilterValue<'_> {
+                fn from(value: $T) -> Self {
+                    FilterValue::Number(value as f64)
+                }
+            }
+        )*
+    };
+}
+
+impl_filter_value_for_number!(f32, f64, u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+/// Helper method to be used by clients to build the list of rows to filter when handling a filter action.
+///
+/// The `value` closure is called for each cell in a filtered column, until a filter does not match.
+///
+/// ```text
+/// match action {
+///     Action::FilterChanged { filters, .. } => {
+///         rows_to_filter = Some(egui_deferred_table::build_rows_to_filter(rows.len(), &filters, |cell_index| {
+///             rows[cell_index.row].get(cell_index.column).into()
+///         }));
+///     }
+///     // ...
+/// }
+///```
+///
+/// See also:
+/// 1. [`crate::DeferredTableRenderer::rows_to_filter`]
+/// 2. [`crate::Action::FilterChanged`]
+///
+pub fn build_rows_to_filter<'a>(
+    row_count: usize,
+    filters: &BTreeMap<usize, ColumnFilter>,
+    mut value: impl FnMut(CellIndex) -> FilterValue<'a>,
+) -> Vec<usize> {
+    // compile any regexes once, not once per row
+    let matchers = filters
+        .iter()
+        .map(|(column, filter)| (*column, ColumnMatcher::new(filter)))
+        .collect::<Vec<_>>();
+
+    (0..row_count)
+        .filter(|row| {
+            !matchers.iter().all(|(column, matcher)| {
+                matcher.matches(&value(CellIndex {
+                    row: *row,
+                    column: *column,
+                }))
+            })
+        })
+        .collect()
+}
+
+fn build_regex(text: &str, case_sensitive: bool) -> Result<Regex, regex::Error> {
+    RegexBuilder::new(text)
+        .case_insensitive(!case_sensitive)
+        .build()
+}
+
+enum ColumnMatcher<'f> {
+    Filter(&'f ColumnFilter),
+    Regex(Regex),
+    /// filters that cannot be used match everything
+    Invalid,
+}
+
+impl<'f> ColumnMatcher<'f> {
+    fn new(filter: &'f ColumnFilter) -> Self {
+        match filter {
+            ColumnFilter::Text {
+                operator: TextFilterOperator::Regex,
+                text,
+                case_sensitive,
+            } => match build_regex(text, *case_sensitive) {
+                Ok(regex) => ColumnMatcher::Regex(regex),
+                Err(_) => ColumnMatcher::Invalid,
+            },
+            _ => ColumnMatcher::Filter(filter),
+        }
+    }
+
+    fn matches(&self, value: &FilterValue) -> bool {
+        match self {
+            ColumnMatcher::Invalid => true,
+            ColumnMatcher::Regex(regex) => regex.is_match(&value.as_text()),
+            ColumnMatcher::Filter(ColumnFilter::Text {
+                operator,
+                text,
+                case_sensitive,
+            }) => {
+                let value = value.as_text();
+                let (value, text) = if *case_sensitive {
+                    (value, Cow::Borrowed(text.as_str()))
+                } else {
+                    (
+                        Cow::Owned(value.to_lowercase()),
+                        Cow::Owned(text.to_lowercase()),
+                    )
+                };
+                match operator {
+                    TextFilterOperator::Contains => value.contains(text.as_ref()),
+                    TextFilterOperator::Equals => value == text,
+                    // handled by `ColumnMatcher::Regex`
+                    TextFilterOperator::Regex => unreachable!(),
+                }
+            }
+            ColumnMatcher::Filter(ColumnFilter::Range { min, max }) => {
+                value.as_number().is_some_and(|number| {
+                    min.is_none_or(|min| number >= min) && max.is_none_or(|max| number <= max)
+                })
+            }
+            ColumnMatcher::Filter(ColumnFilter::Values(values)) => {
+                values.contains(value.as_text().as_ref())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod filter_tests {
+    use crate::{ColumnFilter, FilterValue, TextFilterOperator, build_rows_to_filter};
+    use rstest::rstest;
+    use std::collections::{BTreeMap, BTreeSet};
+
+    fn text(operator: TextFilterOperator, text: &str, case_sensitive: bool) -> ColumnFilter {
+        ColumnFilter::Text {
+            operator,
+            text: text.to_string(),
+            case_sensitive,
+        }
+    }
+
+    fn values(values: &[&str]) -> ColumnFilter {
+        ColumnFilter::Values(
+            values
+                .iter()
+                .map(|it| it.to_string())
+                .collect::<BTreeSet<_>>(),
+        )
+    }
+
+    #[rstest]
+    #[case(text(TextFilterOperator::Contains, "res", false), "Resistor".into(), true)]
+    #[case(text(TextFilterOperator::Contains, "res", true), "Resistor".into(), false)]
+    #[case(
+        text(TextFilterOperator::Contains, "", false),
+        FilterValue::Empty,
+        true
+    )]
+    #[case(text(TextFilterOperator::Equals, "resistor", false), "Resistor".into(), true)]
+    #[case(text(TextFilterOperator::Equals, "res", false), "Resistor".into(), false)]
+    #[case(text(TextFilterOperator::Equals, "10", false), 10_u32.into(), true)]
+    #[case(text(TextFilterOperator::Regex, "^R[0-9]+$", true), "R12".into(), true)]
+    #[case(text(TextFilterOperator::Regex, "^R[0-9]+$", true), "C12".into(), false)]
+    #[case(text(TextFilterOperator::Regex, "^r", false), "R12".into(), true)]
+    // invalid regexes match everything
+    #[case(text(TextFilterOperator::Regex, "(", false), "R12".into(), true)]
+    #[case(ColumnFilter::Range { min: Some(1.0), max: Some(10.0) }, 1.0.into(), true)]
+    #[case(ColumnFilter::Range { min: Some(1.0), max: Some(10.0) }, 10_i32.into(), true)]
+    #[case(ColumnFilter::Range { min: Some(1.0), max: Some(10.0) }, 10.5.into(), false)]
+    #[case(ColumnFilter::Range { min: None, max: Some(10.0) }, (-5.0).into(), true)]
+    #[case(ColumnFilter::Range { min: Some(1.0), max: None }, " 42 ".into(), true)]
+    #[case(ColumnFilter::Range { min: None, max: None }, "abc".into(), false)]
+    #[case(ColumnFilter::Range { min: None, max: None }, FilterValue::Empty, false)]
+    #[case(values(&["0603", "0805"]), "0805".into(), true)]
+    #[case(values(&["0603", "0805"]), "1206".into(), false)]
+    #[case(values(&["", "0805"]), FilterValue::Empty, true)]
+    fn test_matches(
+        #[case] filter: ColumnFilter,
+        #[case] value: FilterValue,
+        #[case] expected: bool,
+    ) {
+        assert_eq!(filter.matches(&value), expected);
+    }
+
+    #[test]
+    fn test_build_rows_to_filter() {
+        // given
+        let rows = [("R1", 10), ("C1", 100), ("R2", 1000), ("R3", 100)];
+        let mut filters = BTreeMap::new();
+        filters.insert(0, text(TextFilterOperator::Contains, "r", false));
+        filters.insert(
+            1,
+            ColumnFilter::Range {
+                min: Some(50.0),
+                max: None,
+            },
+        );
+
+        // when
+        let rows_to_filter = build_rows_to_filter(rows.len(), &filters, |cell_index| {
+            let row = &rows[cell_index.row];
+            match cell_index.column {
+                0 => row.0.into(),
+                1 => row.1.into(),
+                _ => unreachable!(),
+            }
+        });
+
+        // then
+        assert_eq!(rows_to_filter, vec![0, 1]);
+    }
+}
@@ -0,0 +1,147 @@
+use crate::{DeferredTableStrings, Pin, SortDirection};
+use egui::Ui;
+use std::collections::BTreeSet;
+
+/// An item of the built-in column header menu, see [`crate::DeferredTable::column_header_menu`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum ColumnHeaderMenuItem {
+    Sort(SortDirection),
+    Hide,
+    AutoFit,
+    ResetWidth,
+    Pin(Option<Pin>),
+}
+
+/// The items that are enabled in the built-in column header menu of a column.
+pub(crate) struct ColumnHeaderMenu {
+    pub(crate) sortable: bool,
+    /// false for the last visible column, so that at least one column remains visible.
+    pub(crate) hideable: bool,
+    pub(crate) resizable: bool,
+    /// the pin of the column, from its parameters, only the pin items that change it are shown.
+    pub(crate) pin: Option<Pin>,
+}
+
+impl ColumnHeaderMenu {
+    /// shows the items, returns the item that was clicked, if any.  Items that are not enabled are not shown.
+    pub(crate) fn show(
+        &self,
+        ui: &mut Ui,
+        strings: &dyn DeferredTableStrings,
+    ) -> Option<ColumnHeaderMenuItem> {
+        let sections = [
+            (
+                self.sortable,
+                vec![
+                    (
+                        strings.sort_ascending(),
+                        ColumnHeaderMenuItem::Sort(SortDirection::Ascending),
+                    ),
+                    (
+                        strings.sort_descending(),
+                        ColumnHeaderMenuItem::Sort(SortDirection::Descending),
+                    ),
+                ],
+            ),
+            (
+                self.hideable,
+                vec![(strings.hide_column(), ColumnHeaderMenuItem::Hide)],
+            ),
+            (
+                self.resizable,
+                vec![
+                    (strings.auto_fit_width(), ColumnHeaderMenuItem::AutoFit),
+                    (strings.reset_width(), ColumnHeaderMenuItem::ResetWidth),
+                ],
+            ),
+            (
+                true,
+                pin_changes(self.pin)
+                    .map(|pin| {
+                        let label = match pin {
+                            Some(Pin::Start) => strings.pin_left(),
+                            Some(Pin::End) => strings.pin_right(),
+                            None => strings.unpin(),
+                        };
+                        (label, ColumnHeaderMenuItem::Pin(pin))
+                    })
+                    .collect(),
+            ),
+        ];
+
+        let mut clicked = None;
+        let mut first_section = true;
+        for (_, items) in sections.into_iter().filter(|(enabled, _)| *enabled) {
+            if !first_section {
+                ui.separator();
+            }
+            first_section = false;
+
+            for (label, item) in items {
+                if ui.button(label).clicked() {
+                    clicked = Some(item);
+                }
+            }
+        }
+
+        clicked
+    }
+}
+
+/// Returns the pins a column with the given pin can be changed to, in menu order.
+fn pin_changes(pin: Option<Pin>) -> impl Iterator<Item = Option<Pin>> {
+    [Some(Pin::Start), Some(Pin::End), None]
+        .into_iter()
+        .filter(move |it| *it != pin)
+}
+
+/// Returns the columns to hide after showing or hiding a column, using mapped column indexes.
+pub(crate) fn hidden_columns(
+    columns_to_filter: Option<&[usize]>,
+    column: usize,
+    visible: bool,
+) -> BTreeSet<usize> {
+    let mut hidden: BTreeSet<usize> = columns_to_filter
+        .unwrap_or_default()
+        .iter()
+        .copied()
+        .collect();
+    match visible {
+        true => hidden.remove(&column),
+        false => hidden.insert(column),
+    };
+    hidden
+}
+
+#[cfg(test)]
+mod header_menu_tests {
+    use crate::Pin;
+    use crate::header_menu::{hidden_columns, pin_changes};
+    use rstest::rstest;
+    use std::collections::BTreeSet;
+
+    #[rstest]
+    #[case(None, 1, false, BTreeSet::from([1]))]
+    #[case(Some(vec![3, 0]), 1, false, BTreeSet::from([0, 1, 3]))]
+    #[case(Some(vec![3, 1]), 1, true, BTreeSet::from([3]))]
+    #[case(Some(vec![3]), 1, true, BTreeSet::from([3]))]
+    fn test_hidden_columns(
+        #[case] columns_to_filter: Option<Vec<usize>>,
+        #[case] column: usize,
+        #[case] visible: bool,
+        #[case] expected: BTreeSet<usize>,
+    ) {
+        assert_eq!(
+            hidden_columns(columns_to_filter.as_deref(), column, visible),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case(None, vec![Some(Pin::Start), Some(Pin::End)])]
+    #[case(Some(Pin::Start), vec![Some(Pin::End), None])]
+    #[case(Some(Pin::End), vec![Some(Pin::Start), None])]
+    fn test_pin_changes(#[case] pin: Option<Pin>, #[case] expected: Vec<Option<Pin>>) {
+        assert_eq!(pin_changes(pin).collect::<Vec<_>>(), expected);
+    }
+}
@@ -0,0 +1,236 @@
+use crate::CellIndex;
+
+/// An undo/redo history of the changes made using the table, see [`crate::EditorState::with_history`].
+///
+/// Committed edits that changed the value, pastes, column/row re-orderings and column/row resizes that changed the
+/// width/height are recorded, the committed value is obtained using [`crate::EditableTableRenderer::build_item_state`].
+/// Ctrl+Z undoes the most recent change, Ctrl+Y or Ctrl+Shift+Z redoes the most recently undone change.  Recording a
+/// change clears the changes that can be redone.
+///
+/// Cells are reverted using [`crate::EditableTableRenderer::revert`], widths and heights are restored by the table,
+/// re-orderings must be restored by the application, see [`crate::Action::Undo`] and [`HistoryChange`].
+///
+/// The editor's `Value` must implement `Clone` and `PartialEq`, to keep the original values and to detect edits that
+/// didn't change the value.
+#[derive(Debug, Clone)]
+pub struct EditHistory<V> {
+    undo: Vec<HistoryEntry<V>>,
+    redo: Vec<HistoryEntry<V>>,
+    limit: usize,
+    record_reordering: bool,
+    /// `Clone::clone` and `PartialEq::eq` of the values, kept so that only editors with a history need them.
+    clone_value: fn(&V) -> V,
+    eq_value: fn(&V, &V) -> bool,
+}
+
+impl<V: Clone + PartialEq> Default for EditHistory<V> {
+    fn default() -> Self {
+        Self {
+            undo: vec![],
+            redo: vec![],
+            limit: 100,
+            record_reordering: true,
+            clone_value: V::clone,
+            eq_value: V::eq,
+        }
+    }
+}
+
+impl<V> EditHistory<V> {
+    /// the maximum number of changes that can be undone, the oldest changes are discarded.
+    ///
+    /// default: 100
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    /// don't record column/row re-orderings, e.g. when [`crate::Action::ColumnReorder`] is handled by moving the data
+    /// instead of changing the ordering, since the history only records orderings.
+    ///
+    /// default: re-orderings are recorded
+    pub fn ignore_reordering(mut self) -> Self {
+        self.record_reordering = false;
+        self
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+
+    pub(crate) fn records_reordering(&self) -> bool {
+        self.record_reordering
+    }
+
+    pub(crate) fn clone_value(&self, value: &V) -> V {
+        (self.clone_value)(value)
+    }
+
+    pub(crate) fn eq_value(&self, value: &V, other: &V) -> bool {
+        (self.eq_value)(value, other)
+    }
+
+    pub(crate) fn record(&mut self, entry: HistoryEntry<V>) {
+        self.redo.clear();
+        self.undo.push(entry);
+        if self.undo.len() > self.limit {
+            let excess = self.undo.len() - self.limit;
+            self.undo.drain(..excess);
+        }
+    }
+
+    /// removes the entry to revert, the inverse entry, i.e. the entry that reverts the revert, must then be pushed.
+    pub(crate) fn pop(&mut self, step: HistoryStep) -> Option<HistoryEntry<V>> {
+        match step {
+            HistoryStep::Undo => self.undo.pop(),
+            HistoryStep::Redo => self.redo.pop(),
+        }
+    }
+
+    pub(crate) fn push_inverse(&mut self, step: HistoryStep, entry: HistoryEntry<V>) {
+        match step {
+            HistoryStep::Undo => self.redo.push(entry),
+            HistoryStep::Redo => self.undo.push(entry),
+        }
+    }
+
+    /// puts back an entry removed by [`EditHistory::pop`] that could not be reverted.
+    pub(crate) fn restore(&mut self, step: HistoryStep, entry: HistoryEntry<V>) {
+        match step {
+            HistoryStep::Undo => self.undo.push(entry),
+            HistoryStep::Redo => self.redo.push(entry),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum HistoryStep {
+    Undo,
+    Redo,
+}
+
+/// A recorded change, containing the values to restore.
+#[derive(Debug, Clone)]
+pub(crate) enum HistoryEntry<V> {
+    Cells(Vec<(CellIndex, V)>),
+    ColumnOrdering(Option<Vec<usize>>),
+    RowOrdering(Option<Vec<usize>>),
+    ColumnWidth { column: usize, width: f32 },
+    RowHeight { row: usize, height: f32 },
+}
+
+/// A change that was undone or redone, see [`crate::Action::Undo`] and [`crate::Action::Redo`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum HistoryChange {
+    /// the cells, using mapped indexes, were reverted using [`crate::EditableTableRenderer::revert`], no handling is
+    /// required.
+    Cells(Vec<CellIndex>),
+    /// the column ordering to restore, e.g. by replacing the renderer's column ordering.
+    ColumnOrdering(Option<Vec<usize>>),
+    /// the row ordering to restore, e.g. by replacing the renderer's row ordering.
+    RowOrdering(Option<Vec<usize>>),
+    /// the table has already restored the width of the column, no handling is required.
+    ColumnWidth { column: usize, width: f32 },
+    /// the table has already restored the height of the row, no handling is required.
+    RowHeight { row: usize, height: f32 },
+}
+
+#[cfg(test)]
+mod history_tests {
+    use crate::CellIndex;
+    use crate::history::{EditHistory, HistoryEntry, HistoryStep};
+
+    fn cells(value: &str) -> HistoryEntry<String> {
+        HistoryEntry::Cells(vec![(CellIndex { row: 0, column: 0 }, value.to_string())])
+    }
+
+    fn value(entry: Option<HistoryEntry<String>>) -> Option<String> {
+        match entry {
+            Some(HistoryEntry::Cells(cells)) => Some(cells[0].1.clone()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_undo_and_redo() {
+        // given
+        let mut history = EditHistory::default();
+        history.record(cells("a"));
+        history.record(cells("b"));
+
+        // when
+        let undone = history.pop(HistoryStep::Undo);
+        history.push_inverse(HistoryStep::Undo, cells("c"));
+
+        // then
+        assert_eq!(value(undone), Some("b".to_string()));
+        assert!(history.can_undo());
+        assert!(history.can_redo());
+
+        // when
+        let redone = history.pop(HistoryStep::Redo);
+        history.push_inverse(HistoryStep::Redo, cells("b"));
+
+        // then
+        assert_eq!(value(redone), Some("c".to_string()));
+        assert!(!history.can_redo());
+        assert_eq!(value(history.pop(HistoryStep::Undo)), Some("b".to_string()));
+        assert_eq!(value(history.pop(HistoryStep::Undo)), Some("a".to_string()));
+        assert!(!history.can_undo());
+    }
+
+    #[test]
+    fn test_record_clears_redo() {
+        // given
+        let mut history = EditHistory::default();
+        history.record(cells("a"));
+        let _ = history.pop(HistoryStep::Undo);
+        history.push_inverse(HistoryStep::Undo, cells("b"));
+
+        // when
+        history.record(cells("c"));
+
+        // then
+        assert!(!history.can_redo());
+    }
+
+    #[test]
+    fn test_limit() {
+        // given
+        let mut history = EditHistory::default().limit(2);
+
+        // when
+        for value in ["a", "b", "c"] {
+            history.record(cells(value));
+        }
+
+        // then
+        assert_eq!(value(history.pop(HistoryStep::Undo)), Some("c".to_string()));
+        assert_eq!(value(history.pop(HistoryStep::Undo)), Some("b".to_string()));
+        assert!(history.pop(HistoryStep::Undo).is_none());
+    }
+
+    #[test]
+    fn test_restore() {
+        // given
+        let mut history = EditHistory::default();
+        history.record(cells("a"));
+        let undone = history.pop(HistoryStep::Undo).unwrap();
+
+        // when
+        history.restore(HistoryStep::Undo, undone);
+
+        // then
+        assert!(!history.can_redo());
+        assert_eq!(value(history.pop(HistoryStep::Undo)), Some("a".to_string()));
+    }
+}
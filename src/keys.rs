@@ -0,0 +1,339 @@
+use crate::{CellIndex, CellRange, DeferredTableDataSource, TableDimensions};
+use std::collections::{BTreeSet, HashMap};
+use std::mem;
+
+/// The keys of the rows/columns the table state was last used with, kept separately from the table state so that the
+/// keys are not copied each frame.
+///
+/// See [`crate::DeferredTableDataSource::has_row_keys`].
+#[derive(Debug, Default, Clone)]
+pub(crate) struct TableKeys {
+    /// the dimensions and the keys revision the keys were last fetched with.
+    dimensions: Option<TableDimensions>,
+    revision: u64,
+    rows: Vec<u64>,
+    columns: Vec<u64>,
+}
+
+impl TableKeys {
+    /// returns the row and column remappings, either of which is `None` when no remapping is needed.
+    ///
+    /// The keys are only fetched when the dimensions or the keys revision have changed since the last call.
+    pub(crate) fn update<DataSource: DeferredTableDataSource>(
+        &mut self,
+        data_source: &DataSource,
+        dimensions: TableDimensions,
+    ) -> (Option<KeyRemapping>, Option<KeyRemapping>) {
+        let revision = data_source.keys_revision();
+        if self.dimensions == Some(dimensions) && self.revision == revision {
+            return (None, None);
+        }
+        self.dimensions = Some(dimensions);
+        self.revision = revision;
+
+        let rows = update_keys(
+            &mut self.rows,
+            data_source.has_row_keys().then(|| {
+                (0..dimensions.row_count)
+                    .map(|index| data_source.row_key(index))
+                    .collect()
+            }),
+        );
+        let columns = update_keys(
+            &mut self.columns,
+            data_source.has_column_keys().then(|| {
+                (0..dimensions.column_count)
+                    .map(|index| data_source.column_key(index))
+                    .collect()
+            }),
+        );
+
+        (rows, columns)
+    }
+}
+
+/// stores the current keys, if any, and returns the remapping from the previous keys.
+fn update_keys(keys: &mut Vec<u64>, current_keys: Option<Vec<u64>>) -> Option<KeyRemapping> {
+    let Some(current_keys) = current_keys else {
+        keys.clear();
+        return None;
+    };
+
+    let remapping = KeyRemapping::new(keys, &current_keys);
+    *keys = merge_keys(mem::take(keys), current_keys, remapping.is_some());
+    remapping
+}
+
+/// Maps the indexes used on a previous frame to the indexes used on the current frame, using the row/column keys.
+///
+/// See [`crate::DeferredTableDataSource::row_key`].
+pub(crate) struct KeyRemapping {
+    /// indexed by previous index
+    previous_to_current: Vec<Option<usize>>,
+    /// indexed by current index
+    current_to_previous: Vec<Option<usize>>,
+}
+
+impl KeyRemapping {
+    /// Returns `None` when no remapping is needed, i.e. when the keys common to both are at the same indexes, which is
+    /// the case when rows/columns are only added or removed at the end.
+    pub(crate) fn new(previous_keys: &[u64], current_keys: &[u64]) -> Option<Self> {
+        let common = previous_keys.len().min(current_keys.len());
+        if previous_keys[..common] == current_keys[..common] {
+            return None;
+        }
+
+        let current_indexes = current_keys
+            .iter()
+            .enumerate()
+            .map(|(index, key)| (*key, index))
+            .collect::<HashMap<_, _>>();
+        let previous_indexes = previous_keys
+            .iter()
+            .enumerate()
+            .map(|(index, key)| (*key, index))
+            .collect::<HashMap<_, _>>();
+
+        Some(Self {
+            previous_to_current: previous_keys
+                .iter()
+                .map(|key| current_indexes.get(key).copied())
+                .collect(),
+            current_to_previous: current_keys
+                .iter()
+                .map(|key| previous_indexes.get(key).copied())
+                .collect(),
+        })
+    }
+
+    /// the current index of a previous index, `None` if the key of the previous index no-longer exists.
+    pub(crate) fn index(&self, previous_index: usize) -> Option<usize> {
+        self.previous_to_current
+            .get(previous_index)
+            .copied()
+            .flatten()
+    }
+
+    pub(crate) fn set(&self, previous_indexes: &BTreeSet<usize>) -> BTreeSet<usize> {
+        previous_indexes
+            .iter()
+            .filter_map(|index| self.index(*index))
+            .collect()
+    }
+
+    /// returns the previous values in the current order, using `default` for indexes with new keys.
+    pub(crate) fn values<T: Copy>(
+        &self,
+        previous_values: &[T],
+        default: impl Fn(usize) -> T,
+    ) -> Vec<T> {
+        self.current_to_previous
+            .iter()
+            .enumerate()
+            .map(|(index, previous_index)| {
+                previous_index
+                    .and_then(|previous_index| previous_values.get(previous_index).copied())
+                    .unwrap_or_else(|| default(index))
+            })
+            .collect()
+    }
+}
+
+/// Remaps cell indexes using the row and column remappings, either of which can be `None` when no remapping is needed.
+pub(crate) struct CellRemapping<'a> {
+    pub(crate) rows: Option<&'a KeyRemapping>,
+    pub(crate) columns: Option<&'a KeyRemapping>,
+}
+
+impl CellRemapping<'_> {
+    /// the current index of a previous cell index, `None` if the key of the row or column no-longer exists.
+    pub(crate) fn cell_index(&self, cell_index: CellIndex) -> Option<CellIndex> {
+        let row = match self.rows {
+            Some(rows) => rows.index(cell_index.row)?,
+            None => cell_index.row,
+        };
+        let column = match self.columns {
+            Some(columns) => columns.index(cell_index.column)?,
+            None => cell_index.column,
+        };
+
+        Some(CellIndex { row, column })
+    }
+
+    /// ranges with a corner that no-longer exists are removed.
+    pub(crate) fn cell_ranges(&self, ranges: &[CellRange]) -> Vec<CellRange> {
+        ranges
+            .iter()
+            .filter_map(|range| {
+                Some(CellRange::new(
+                    self.cell_index(range.start)?,
+                    self.cell_index(range.end)?,
+                ))
+            })
+            .collect()
+    }
+}
+
+/// Returns the keys to store for the next update.
+///
+/// When no remapping was needed the keys of rows/columns that no-longer exist are retained, since the widths/heights
+/// of such rows/columns are also retained.
+fn merge_keys(previous_keys: Vec<u64>, current_keys: Vec<u64>, remapped: bool) -> Vec<u64> {
+    if remapped || current_keys.len() >= previous_keys.len() {
+        current_keys
+    } else {
+        let mut keys = current_keys;
+        keys.extend_from_slice(&previous_keys[keys.len()..]);
+        keys
+    }
+}
+
+#[cfg(test)]
+mod key_remapping_tests {
+    use crate::keys::{CellRemapping, KeyRemapping, TableKeys, merge_keys};
+    use crate::{CellIndex, CellRange, DeferredTableDataSource, TableDimensions};
+    use rstest::rstest;
+    use std::cell::Cell;
+    use std::collections::BTreeSet;
+
+    struct KeyedSource {
+        row_keys: Vec<u64>,
+        revision: u64,
+        /// the number of times a key was fetched
+        fetched: Cell<usize>,
+    }
+
+    impl DeferredTableDataSource for KeyedSource {
+        fn get_dimensions(&self) -> TableDimensions {
+            TableDimensions {
+                row_count: self.row_keys.len(),
+                column_count: 2,
+            }
+        }
+
+        fn has_row_keys(&self) -> bool {
+            true
+        }
+
+        fn row_key(&self, index: usize) -> u64 {
+            self.fetched.set(self.fetched.get() + 1);
+            self.row_keys[index]
+        }
+
+        fn keys_revision(&self) -> u64 {
+            self.revision
+        }
+    }
+
+    fn cell(row: usize, column: usize) -> CellIndex {
+        CellIndex { row, column }
+    }
+
+    #[rstest]
+    #[case(vec![], vec![10, 11])]
+    #[case(vec![10, 11], vec![10, 11, 12])]
+    #[case(vec![10, 11, 12], vec![10, 11])]
+    fn test_no_remapping_needed(#[case] previous_keys: Vec<u64>, #[case] current_keys: Vec<u64>) {
+        assert!(KeyRemapping::new(&previous_keys, &current_keys).is_none());
+    }
+
+    #[test]
+    fn test_row_inserted_at_start() {
+        // given
+        let remapping = KeyRemapping::new(&[10, 11, 12], &[13, 10, 11, 12]).unwrap();
+
+        // then
+        assert_eq!(remapping.index(0), Some(1));
+        assert_eq!(remapping.index(2), Some(3));
+        assert_eq!(remapping.index(3), None);
+        assert_eq!(
+            remapping.set(&BTreeSet::from([0, 2])),
+            BTreeSet::from([1, 3])
+        );
+        assert_eq!(
+            remapping.values(&[1.0, 2.0, 3.0], |_| 0.0),
+            vec![0.0, 1.0, 2.0, 3.0]
+        );
+    }
+
+    #[test]
+    fn test_row_removed_from_start() {
+        // given
+        let remapping = KeyRemapping::new(&[10, 11, 12], &[11, 12]).unwrap();
+
+        // then
+        assert_eq!(remapping.index(0), None);
+        assert_eq!(remapping.set(&BTreeSet::from([0, 2])), BTreeSet::from([1]));
+        assert_eq!(remapping.values(&[1.0, 2.0, 3.0], |_| 0.0), vec![2.0, 3.0]);
+    }
+
+    #[test]
+    fn test_cell_ranges() {
+        // given
+        let rows = KeyRemapping::new(&[10, 11, 12], &[12, 11]).unwrap();
+        let remapping = CellRemapping {
+            rows: Some(&rows),
+            columns: None,
+        };
+        let ranges = [
+            CellRange::new(cell(1, 0), cell(2, 3)),
+            CellRange::new(cell(0, 0), cell(1, 1)),
+        ];
+
+        // when
+        let ranges = remapping.cell_ranges(&ranges);
+
+        // then
+        // the second range is removed, since the row with key `10` no-longer exists
+        assert_eq!(ranges, vec![CellRange::new(cell(1, 0), cell(0, 3))]);
+    }
+
+    #[rstest]
+    #[case(vec![10, 11, 12], vec![10], false, vec![10, 11, 12])]
+    #[case(vec![10, 11, 12], vec![11], true, vec![11])]
+    #[case(vec![10], vec![10, 11], false, vec![10, 11])]
+    fn test_merge_keys(
+        #[case] previous_keys: Vec<u64>,
+        #[case] current_keys: Vec<u64>,
+        #[case] remapped: bool,
+        #[case] expected: Vec<u64>,
+    ) {
+        assert_eq!(merge_keys(previous_keys, current_keys, remapped), expected);
+    }
+
+    #[test]
+    fn test_table_keys() {
+        // given
+        let mut source = KeyedSource {
+            row_keys: vec![10, 11, 12],
+            revision: 0,
+            fetched: Cell::new(0),
+        };
+        let mut keys = TableKeys::default();
+        keys.update(&source, source.get_dimensions());
+
+        // when unchanged
+        let (rows, columns) = keys.update(&source, source.get_dimensions());
+
+        // then the keys are not fetched again
+        assert!(rows.is_none() && columns.is_none());
+        assert_eq!(source.fetched.get(), 3);
+
+        // when a row is replaced, without changing the dimensions
+        source.row_keys[0] = 13;
+        source.revision += 1;
+        let (rows, columns) = keys.update(&source, source.get_dimensions());
+
+        // then
+        assert_eq!(rows.and_then(|rows| rows.index(0)), None);
+        assert!(columns.is_none());
+        assert_eq!(source.fetched.get(), 6);
+
+        // when a row is inserted at the start
+        source.row_keys.insert(0, 14);
+        let (rows, _) = keys.update(&source, source.get_dimensions());
+
+        // then
+        assert_eq!(rows.and_then(|rows| rows.index(0)), Some(1));
+    }
+}
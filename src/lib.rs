@@ -1,34 +1,83 @@
+use clipboard::{copied_cells, pasted_cells};
+use egui::containers::menu::{MenuConfig, SubMenuButton};
 use egui::emath::GuiRounding;
 use egui::scroll_area::ScrollBarVisibility;
 use egui::{
-    Color32, Context, CornerRadius, Frame, Id, Margin, NumExt, Painter, PointerButton, PopupAnchor,
-    Pos2, Rangef, Rect, Response, RichText, Sense, Shadow, Stroke, StrokeKind, Style, Tooltip, Ui,
-    UiBuilder, UiKind, UiStackInfo, Vec2,
+    Color32, Context, CornerRadius, Event, FocusDirection, Frame, Id, Key, Margin, Modifiers,
+    NumExt, Painter, PointerButton, Popup, PopupAnchor, PopupCloseBehavior, Pos2, Rangef, Rect,
+    Response, RichText, Sense, SetOpenCommand, Shadow, Shape, Stroke, StrokeKind, Style, TextEdit,
+    Tooltip, Ui, UiBuilder, UiKind, UiStackInfo, Vec2,
 };
+use header_menu::{ColumnHeaderMenu, ColumnHeaderMenuItem, hidden_columns};
+use history::{HistoryEntry, HistoryStep};
+use keys::{CellRemapping, TableKeys};
 use log::{info, trace};
-use std::collections::BTreeSet;
+use memo::Memo;
+use navigation::Navigation;
+use ordering::{map_index, visible_indexes};
+use pinning::Pinning;
+use pointer::{CellClick, CellPress};
+use row_groups::{GroupAggregates, GroupRows, LayoutRow, RowGroups, RowLayout};
+use search::{SearchInputs, SearchMatches, SearchState};
+use selection::{VisibleCellSelection, apply_axis_selection};
+use spans::{CellSpans, SpanAxis};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Display;
 use std::marker::PhantomData;
+use std::mem;
 use std::ops::{Add, Range, Sub};
+use std::sync::Arc;
+use tree::collapsed_rows_to_filter;
 
 mod actions;
 mod cells;
+mod clipboard;
+mod column_chooser;
+mod column_groups;
 mod data_source;
 mod dimensions;
 mod editing;
+mod filtering;
+mod header_menu;
+mod history;
+mod keys;
+mod memo;
+mod navigation;
 mod ordering;
 mod parameters;
+mod pinning;
+mod pointer;
+mod row_groups;
+mod search;
+mod selection;
 mod slices;
+mod sorting;
+mod spans;
+mod state;
+mod strings;
 mod table_renderer;
+mod tree;
 
 pub use actions::*;
 pub use cells::*;
+pub use clipboard::CopyFormat;
+pub use column_chooser::ColumnChooser;
+pub use column_groups::ColumnGroup;
 pub use data_source::*;
 pub use dimensions::*;
 pub use editing::*;
+pub use filtering::*;
+pub use history::{EditHistory, HistoryChange};
 pub use ordering::*;
 pub use parameters::*;
+pub use selection::*;
 pub use slices::*;
+pub use sorting::*;
+pub use spans::CellSpan;
+pub use state::DeferredTableState;
+pub use strings::*;
 pub use table_renderer::*;
+pub use tree::TreeRow;
 
 const SHOW_HEADER_CELL_BORDERS: bool = false;
 const SHOW_CELL_BORDERS: bool = false;
@@ -45,6 +94,8 @@ const EDITOR_FRAME: Frame = Frame {
 pub struct DeferredTable<'a, DataSource> {
     id: Id,
     parameters: DeferredTableParameters<'a>,
+    group_rows: Option<GroupRows<'a, DataSource>>,
+    group_aggregates: Option<GroupAggregates<'a, DataSource>>,
     phantom_data: PhantomData<DataSource>,
 }
 
@@ -53,6 +104,8 @@ impl<'a, DataSource> DeferredTable<'a, DataSource> {
         Self {
             id,
             parameters: DeferredTableParameters::default(),
+            group_rows: None,
+            group_aggregates: None,
             phantom_data: PhantomData,
         }
     }
@@ -75,18 +128,208 @@ impl<'a, DataSource> DeferredTable<'a, DataSource> {
         self
     }
 
+    /// clicking on a row header selects the row, shift+click selects a range of rows and ctrl+click toggles a row,
+    /// see [`Action::RowSelectionChanged`].
+    ///
     /// default: enabled,
     pub fn selectable_rows(mut self) -> Self {
         self.parameters.selectable_rows = true;
         self
     }
 
+    /// dragging an unselected row header selects the rows under the pointer, instead of re-ordering the row, dragging a
+    /// selected row header still re-orders it.  Only applicable when the rows are selectable.
+    ///
+    /// default: disabled, dragging a row header re-orders the row.
+    pub fn drag_select_rows(mut self) -> Self {
+        self.parameters.drag_select_rows = true;
+        self
+    }
+
     /// default: selectable-rows enabled
     pub fn selectable_rows_disabled(mut self) -> Self {
         self.parameters.selectable_rows = false;
         self
     }
 
+    /// clicking on a cell selects it, shift+click or dragging selects a rectangular range of cells, and ctrl+click adds
+    /// another range, see [`Action::CellSelectionChanged`].
+    ///
+    /// default: disabled
+    pub fn selectable_cells(mut self) -> Self {
+        self.parameters.selectable_cells = true;
+        self
+    }
+
+    /// clicking on a column header selects the column, shift+click selects a range of columns and ctrl+click toggles a
+    /// column, see [`Action::ColumnSelectionChanged`].
+    ///
+    /// When the columns are also sortable, clicking on the sort indicator area of a column header sorts the column.
+    ///
+    /// default: disabled
+    pub fn selectable_columns(mut self) -> Self {
+        self.parameters.selectable_columns = true;
+        self
+    }
+
+    /// clicking on a column header cycles the sort direction of the column, see [`Action::SortChanged`].
+    /// shift+clicking sorts by multiple columns.
+    ///
+    /// Individual columns can be excluded via [`AxisParameters::sortable`].
+    ///
+    /// default: disabled
+    pub fn sortable_columns(mut self) -> Self {
+        self.parameters.sortable_columns = true;
+        self
+    }
+
+    /// double-clicking on a cell starts editing it, instead of clicking on the active cell, see
+    /// [`DeferredTable::show_and_edit`].
+    ///
+    /// default: disabled
+    pub fn edit_on_double_click(mut self) -> Self {
+        self.parameters.edit_on_double_click = true;
+        self
+    }
+
+    /// shows a search bar above the table, matching cells are highlighted and the table can be filtered to just the rows
+    /// with matching cells.  Ctrl+F focuses the search bar when the pointer is over the table.
+    ///
+    /// The text of each cell is obtained via [`DeferredTableDataSource::search_text`].  The cells are searched every
+    /// frame while there is a query, unless the data source implements [`DeferredTableDataSource::data_revision`], then
+    /// the matches are kept until the query, the data revision, the dimensions, the row/column ordering or the filtered
+    /// rows/columns change.
+    ///
+    /// default: disabled
+    pub fn search_bar(mut self) -> Self {
+        self.parameters.search_bar = true;
+        self
+    }
+
+    /// secondary-clicking (right-clicking) on a column header shows a menu to sort, hide, auto-fit, reset the width of, and
+    /// pin the column, see [`Action::SortChanged`], [`Action::ColumnVisibilityChanged`], [`Action::ColumnWidthChanged`]
+    /// and [`Action::ColumnPinChanged`].  Only the pin items that change the column's [`AxisParameters::pinned`] are
+    /// shown, e.g. "Unpin" is not shown for an unpinned column.
+    ///
+    /// Auto-fitting only uses the header and the cells of the column that were shown on the previous frame.  The items of
+    /// [`DeferredTableRenderer::header_context_menu`], if any, are shown above the built-in items.
+    ///
+    /// default: disabled
+    pub fn column_header_menu(mut self) -> Self {
+        self.parameters.column_header_menu = true;
+        self
+    }
+
+    /// secondary-clicking (right-clicking) on the corner cell shows a [`ColumnChooser`], to hide, show and re-order the
+    /// columns, see [`Action::ColumnVisibilityChanged`] and [`Action::ColumnReorder`].  When the column header menu is
+    /// also enabled, it contains the column chooser as a sub-menu.
+    ///
+    /// default: disabled
+    pub fn column_chooser(mut self) -> Self {
+        self.parameters.column_chooser = true;
+        self
+    }
+
+    /// keeps the first `count` visible columns visible at the left when scrolling horizontally, in addition to the
+    /// columns pinned using [`AxisParameters::pinned`].
+    ///
+    /// default: 0
+    pub fn frozen_columns(mut self, count: usize) -> Self {
+        self.parameters.frozen_columns = count;
+        self
+    }
+
+    /// keeps the first `count` visible rows visible at the top when scrolling vertically, in addition to the rows pinned
+    /// using [`AxisParameters::pinned`].
+    ///
+    /// default: 0
+    pub fn frozen_rows(mut self, count: usize) -> Self {
+        self.parameters.frozen_rows = count;
+        self
+    }
+
+    /// shows header bands above the column headers, each spanning a group of columns, see [`ColumnGroup`].
+    ///
+    /// Dragging a group header onto a column header moves the whole group, see [`Action::ColumnGroupReorder`].
+    ///
+    /// default: no groups
+    pub fn column_groups(mut self, groups: &'a [ColumnGroup]) -> Self {
+        self.parameters.column_groups = groups;
+        self
+    }
+
+    /// groups the rows by the key that `key` returns for each (mapped) row index, a group header row is shown above each
+    /// run of adjacent rows with the same key, showing the key, the number of rows and, optionally, the aggregates, see
+    /// [`DeferredTable::group_aggregates`].
+    ///
+    /// The runs are found in visible order, so the row ordering is applied first, e.g. sort the rows by the grouped
+    /// column to get one group per key.  Filtered rows are not counted, and pinned rows are not grouped.  Clicking a group
+    /// header collapses or expands the group, see [`Action::RowGroupExpansionChanged`], groups are collapsed by key, so
+    /// separate runs with the same key are collapsed together.  The header of the group of the top-most row stays at the
+    /// top while scrolling.
+    ///
+    /// `key` is called for every row, every frame, or only when the row count, ordering or filter, or the
+    /// [`DeferredTableDataSource::data_revision`], change, if the data revision is implemented.  Each key is compared with
+    /// the key of the previous row, and only the key of the first row of each group is shown, using [`Display`], so a
+    /// cheap key, e.g. a number or a `&str`, can be used.
+    ///
+    /// The rows of collapsed groups are filtered in the same way as the rows filtered by the renderer, so they are skipped
+    /// when navigating, selecting, copying and pasting.
+    ///
+    /// default: not grouped
+    pub fn group_rows_by<K: PartialEq + Display>(
+        mut self,
+        key: impl Fn(&DataSource, usize) -> K + 'a,
+    ) -> Self {
+        self.group_rows = Some(Box::new(
+            move |data_source, count, ordering, filter, scrolled| {
+                RowGroups::new(count, ordering, filter, scrolled, |index| {
+                    key(data_source, index)
+                })
+            },
+        ));
+        self
+    }
+
+    /// the text shown after the label of each group header, e.g. the sum of a column, `aggregates` is called with the
+    /// mapped indexes of the rows of the group, in visible order, excluding filtered rows.
+    ///
+    /// Only used when the rows are grouped, see [`DeferredTable::group_rows_by`], it's called for the shown groups.
+    ///
+    /// default: no aggregates
+    pub fn group_aggregates(
+        mut self,
+        aggregates: impl Fn(&DataSource, &[usize]) -> String + 'a,
+    ) -> Self {
+        self.group_aggregates = Some(Box::new(aggregates));
+        self
+    }
+
+    /// the format of the text copied to the clipboard when Ctrl+C is pressed.
+    ///
+    /// The selected cells, or else the selected rows, or else the selected columns, or else the active cell, are copied in
+    /// visible order, excluding filtered rows and columns.  The text of each cell is obtained via
+    /// [`DeferredTableRenderer::cell_text`].
+    ///
+    /// When editing, the format is also used to parse text pasted using Ctrl+V, see [`CopyFormat::parse`].
+    ///
+    /// [`CopyFormat::Html`] copies tab-separated values as the text and returns the HTML using [`Action::CopiedHtml`].
+    ///
+    /// default: [`CopyFormat::Tsv`]
+    pub fn copy_format(mut self, format: CopyFormat) -> Self {
+        self.parameters.copy_format = format;
+        self
+    }
+
+    /// the strings used by the built-in UI, e.g. the search bar, filter popups, the column header menu and the column
+    /// chooser.
+    ///
+    /// default: [`DefaultStrings`], English
+    pub fn strings(mut self, strings: &'a dyn DeferredTableStrings) -> Self {
+        self.parameters.strings = strings;
+        self
+    }
+
     /// default: disabled
     pub fn highlight_hovered_cell(mut self) -> Self {
         self.parameters.highlight_hovered_cell = true;
@@ -124,6 +367,13 @@ impl<'a, DataSource> DeferredTable<'a, DataSource> {
         self.show_outer::<Renderer, NullEditor, _, _>(ui, data_source, renderer, editor, edit_state)
     }
 
+    /// Shows the table, with edit-in-place support, see [`EditableTableRenderer`].
+    ///
+    /// Click the active cell, press Enter/F2, or start typing, to edit it.  While editing, Escape cancels the edit,
+    /// Tab/Shift+Tab and Enter/Shift+Enter complete the edit and move the active cell right/left and down/up,
+    /// respectively.
+    ///
+    /// Changes can be undone and redone using Ctrl+Z and Ctrl+Y when the edit state has an [`EditHistory`].
     pub fn show_and_edit<Renderer, Editor, IS, V>(
         self,
         ui: &mut Ui,
@@ -170,6 +420,24 @@ impl<'a, DataSource> DeferredTable<'a, DataSource> {
         result
     }
 
+    /// the sanitized default width/height of a column/row, or `fallback` if there is no default.
+    fn default_dimension(
+        axis_parameters: Option<&Vec<AxisParameters>>,
+        index: usize,
+        fallback: f32,
+    ) -> f32 {
+        let Some(parameters) = axis_parameters.and_then(|it| it.get(index)) else {
+            return fallback;
+        };
+        match parameters.default_dimension {
+            Some(default_dimension) if parameters.resizable => {
+                parameters.dimension_range.clamp(default_dimension)
+            }
+            Some(default_dimension) => default_dimension,
+            None => fallback,
+        }
+    }
+
     fn outer_size(cell_size: Vec2, style: &Style) -> Vec2 {
         cell_size + style.spacing.item_spacing
     }
@@ -223,6 +491,11 @@ impl<'a, DataSource> DeferredTable<'a, DataSource> {
 
         let outer_cell_size = Self::outer_size(inner_cell_size, style);
 
+        // the column group rows are above the column headers, the header size includes them.
+        let column_group_levels = column_groups::level_count(self.parameters.column_groups);
+        let column_groups_height = column_group_levels as f32 * (outer_cell_size.y + 1.0);
+        let header_size = outer_cell_size + Vec2::new(0.0, column_groups_height);
+
         // FIXME if the column/row is too narrow/short then the hover/drag isn't detected, even though it's visible.
         //       to replicate, set 3 columns/rows to their minimum width/heights and then try resizing the middle one.
         //       as a workaround we clamp the minimum column/row width/heights to this.
@@ -230,6 +503,8 @@ impl<'a, DataSource> DeferredTable<'a, DataSource> {
 
         let mut clear_drag_state = false;
         let mut request_row_selection_changed_action = false;
+        let mut request_cell_selection_changed_action = false;
+        let mut request_column_selection_changed_action = false;
 
         // TODO allow these to be overridden
         let default_column_parameters = AxisParameters::default();
@@ -240,17 +515,139 @@ impl<'a, DataSource> DeferredTable<'a, DataSource> {
             SetHeight(usize, f32),
         }
         let mut drag_action = None;
+        // the widths/heights of the columns/rows before they were resized, recorded when resizing stops, for the edit
+        // history.
+        let mut resize_entries = vec![];
+        // the widths of the contents of the column headers and cells shown on this frame, keyed by mapped column index.
+        let mut content_widths: BTreeMap<usize, f32> = BTreeMap::new();
+
+        enum SelectionAction {
+            /// select all, or clear the selection if everything is already selected
+            Toggle,
+            SelectAll,
+            Clear,
+        }
+        let mut selection_action = None;
 
         let pointer_pos = ui.ctx().pointer_latest_pos();
+        let input_options = ctx.options(|options| options.input_options);
 
-        let temp_state_id = self.id.with("temp_state");
+        let temp_state_id = state::temp_state_id(self.id);
         let mut temp_state = DeferredTableTempState::load_or_default(&ctx, temp_state_id);
 
-        let persistent_state_id = self.id.with("persistent_state");
+        let persistent_state_id = state::persistent_state_id(self.id);
         let mut state = DeferredTablePersistentState::load_or_default(&ctx, persistent_state_id);
 
         trace!("dimensions: {:?}", dimensions);
 
+        // keep the state with the same rows/columns when rows/columns are inserted or removed, since the state uses
+        // mapped indexes.  See `DeferredTableDataSource::has_row_keys`.
+        let (row_remapping, column_remapping) = ctx.data_mut(|data| {
+            data.get_temp_mut_or_default::<TableKeys>(state::keys_id(self.id))
+                .update(data_source, dimensions)
+        });
+
+        if let Some(rows) = &row_remapping {
+            let row_selections = rows.set(&temp_state.row_selections);
+            if row_selections != temp_state.row_selections {
+                temp_state.row_selections = row_selections;
+                request_row_selection_changed_action = true;
+            }
+            temp_state.row_selection_anchor = temp_state
+                .row_selection_anchor
+                .and_then(|index| rows.index(index));
+            temp_state.selecting_rows = None;
+            temp_state.collapsed_rows = rows.set(&temp_state.collapsed_rows);
+
+            state.row_heights = rows.values(&state.row_heights, |index| {
+                Self::default_dimension(self.parameters.row_parameters, index, inner_cell_size.y)
+            });
+        }
+
+        if let Some(columns) = &column_remapping {
+            let column_selections = columns.set(&temp_state.column_selections);
+            if column_selections != temp_state.column_selections {
+                temp_state.column_selections = column_selections;
+                request_column_selection_changed_action = true;
+            }
+            temp_state.column_selection_anchor = temp_state
+                .column_selection_anchor
+                .and_then(|index| columns.index(index));
+
+            temp_state.sort = SortSpec::from(
+                temp_state
+                    .sort
+                    .keys()
+                    .iter()
+                    .filter_map(|(column, direction)| Some((columns.index(*column)?, *direction)))
+                    .collect::<Vec<_>>(),
+            );
+            temp_state.filters = mem::take(&mut temp_state.filters)
+                .into_iter()
+                .filter_map(|(column, filter)| Some((columns.index(column)?, filter)))
+                .collect();
+            temp_state.content_widths = mem::take(&mut temp_state.content_widths)
+                .into_iter()
+                .filter_map(|(column, width)| Some((columns.index(column)?, width)))
+                .collect();
+
+            state.column_widths = columns.values(&state.column_widths, |index| {
+                Self::default_dimension(self.parameters.column_parameters, index, inner_cell_size.x)
+            });
+        }
+
+        if row_remapping.is_some() || column_remapping.is_some() {
+            let cells = CellRemapping {
+                rows: row_remapping.as_ref(),
+                columns: column_remapping.as_ref(),
+            };
+
+            let cell_selections = cells.cell_ranges(&temp_state.cell_selections);
+            if cell_selections != temp_state.cell_selections {
+                temp_state.cell_selections = cell_selections;
+                request_cell_selection_changed_action = true;
+            }
+            temp_state.selecting_cells = false;
+            temp_state.search.current = temp_state
+                .search
+                .current
+                .and_then(|cell_index| cells.cell_index(cell_index));
+            temp_state.scroll_to = temp_state
+                .scroll_to
+                .and_then(|cell_index| cells.cell_index(cell_index));
+            temp_state.active_cell = temp_state
+                .active_cell
+                .and_then(|cell_index| cells.cell_index(cell_index));
+
+            // the edit is discarded if the row or column of the cell being edited no-longer exists.
+            if let Some(edit_state) = edit_state.as_mut() {
+                edit_state.state = match edit_state.state.take() {
+                    Some(CellEditState::Pivot(cell_index)) => {
+                        cells.cell_index(cell_index).map(CellEditState::Pivot)
+                    }
+                    Some(CellEditState::Editing(cell_index, item_state, original_item)) => {
+                        cells.cell_index(cell_index).map(|cell_index| {
+                            CellEditState::Editing(cell_index, item_state, original_item)
+                        })
+                    }
+                    None => None,
+                };
+            }
+        }
+
+        // when an editor is used, the active cell is the cell of the edit state, which the application can also change.
+        let editing = if let Some(edit_state) = edit_state.as_ref() {
+            temp_state.active_cell = match &edit_state.state {
+                Some(CellEditState::Pivot(cell_index))
+                | Some(CellEditState::Editing(cell_index, _, _)) => Some(*cell_index),
+                None => None,
+            };
+            matches!(edit_state.state, Some(CellEditState::Editing(..)))
+        } else {
+            false
+        };
+        let previous_active_cell = temp_state.active_cell;
+
         let dimensions_changed = temp_state
             .dimensions
             .map_or(true, |previous_frame_dimensions| {
@@ -269,12 +666,200 @@ impl<'a, DataSource> DeferredTable<'a, DataSource> {
                 }
                 visible
             });
+
+            temp_state.column_selections.retain(|&mapped_column_index| {
+                let visible = mapped_column_index < dimensions.column_count;
+
+                if !visible {
+                    request_column_selection_changed_action = true;
+                }
+                visible
+            });
+
+            temp_state
+                .collapsed_rows
+                .retain(|&mapped_row_index| mapped_row_index < dimensions.row_count);
+
+            let cell_selection_count = temp_state.cell_selections.len();
+            temp_state
+                .cell_selections
+                .retain(|range| range.is_within(dimensions));
+            if temp_state.cell_selections.len() != cell_selection_count {
+                request_cell_selection_changed_action = true;
+            }
         }
 
         let parent_max_rect = ui.max_rect();
         let parent_clip_rect = ui.clip_rect();
         let ui_layer_id = ui.layer_id();
 
+        let renderer_column_ordering = renderer.column_ordering().unwrap_or_default();
+        let renderer_row_ordering = renderer.row_ordering().unwrap_or_default();
+        let columns_to_filter = renderer.columns_to_filter();
+        // the values found using the data source are kept while the data revision is unchanged, see `Memo`.
+        let data_revision = data_source.data_revision();
+        // the descendants of collapsed rows are filtered in the same way as the rows filtered by the renderer.
+        let tree_rows_to_filter = (!temp_state.collapsed_rows.is_empty()).then(|| {
+            temp_state.collapsed_rows_filter.get(
+                data_revision,
+                (
+                    dimensions,
+                    &temp_state.collapsed_rows,
+                    renderer.rows_to_filter(),
+                ),
+                || {
+                    collapsed_rows_to_filter(
+                        dimensions.row_count,
+                        &temp_state.collapsed_rows,
+                        renderer.rows_to_filter(),
+                        |index| {
+                            data_source
+                                .tree_row(index)
+                                .and_then(|tree_row| tree_row.parent)
+                        },
+                    )
+                },
+            )
+        });
+        let renderer_rows_to_filter = tree_rows_to_filter
+            .as_deref()
+            .map(Vec::as_slice)
+            .or(renderer.rows_to_filter());
+
+        // pinned columns/rows are moved to the start or end of the ordering, so the visible order is the shown order.
+        let pins = |axis_parameters: Option<&Vec<AxisParameters>>| {
+            axis_parameters
+                .into_iter()
+                .flatten()
+                .enumerate()
+                .filter_map(|(index, it)| it.pinned.map(|pin| (index, pin)))
+                .collect::<Vec<_>>()
+        };
+        let column_pinning = Pinning::new(
+            dimensions.column_count,
+            renderer_column_ordering,
+            columns_to_filter,
+            self.parameters.frozen_columns,
+            pins(self.parameters.column_parameters),
+        );
+        let row_pinning = Pinning::new(
+            dimensions.row_count,
+            renderer_row_ordering,
+            renderer_rows_to_filter,
+            self.parameters.frozen_rows,
+            pins(self.parameters.row_parameters),
+        );
+        let column_ordering = column_pinning.ordering(renderer_column_ordering);
+        let row_ordering = row_pinning.ordering(renderer_row_ordering);
+
+        // the rows are grouped in visible order, the rows of collapsed groups are filtered in the same way as the rows
+        // filtered by the renderer, and a group header row is laid out above the first row of each group, see `RowLayout`.
+        let scrolled_rows = row_pinning.scrolled_range(dimensions.row_count);
+        let group_inputs = (
+            dimensions,
+            row_ordering,
+            renderer_rows_to_filter,
+            scrolled_rows.clone(),
+        );
+        let row_groups = self.group_rows.as_ref().map(|group_rows| {
+            temp_state.row_groups.get(data_revision, &group_inputs, || {
+                group_rows(
+                    data_source,
+                    dimensions.row_count,
+                    row_ordering,
+                    renderer_rows_to_filter,
+                    scrolled_rows,
+                )
+            })
+        });
+        let group_rows_to_filter = row_groups
+            .as_ref()
+            .filter(|_| !temp_state.collapsed_groups.is_empty())
+            .map(|row_groups| {
+                temp_state.collapsed_groups_filter.get(
+                    data_revision,
+                    (&group_inputs, &temp_state.collapsed_groups),
+                    || {
+                        row_groups
+                            .rows_to_filter(&temp_state.collapsed_groups, renderer_rows_to_filter)
+                    },
+                )
+            });
+        let renderer_rows_to_filter = group_rows_to_filter
+            .as_deref()
+            .map(Vec::as_slice)
+            .or(renderer_rows_to_filter);
+
+        let mut search_bar_height = 0.0;
+        let search_matches = if self.parameters.search_bar {
+            let search_bar_top = ui.next_widget_position().y;
+
+            let visible_columns =
+                visible_indexes(dimensions.column_count, column_ordering, columns_to_filter)
+                    .collect::<Vec<_>>();
+            let search_inputs = SearchInputs {
+                data_revision,
+                dimensions,
+                row_ordering,
+                column_ordering,
+                rows_to_filter: renderer_rows_to_filter,
+                columns_to_filter,
+            };
+            let (search_matches, scroll_to) = Self::show_search_bar(
+                ui,
+                self.parameters.strings,
+                &mut temp_state.search,
+                search_inputs,
+                |query| {
+                    let cells = visible_indexes(
+                        dimensions.row_count,
+                        row_ordering,
+                        renderer_rows_to_filter,
+                    )
+                    .flat_map(|row| {
+                        visible_columns
+                            .iter()
+                            .map(move |&column| CellIndex { row, column })
+                    });
+                    SearchMatches::find(query, cells, |cell_index| {
+                        data_source.search_text(cell_index)
+                    })
+                },
+            );
+            if scroll_to.is_some() {
+                temp_state.scroll_to = scroll_to;
+            }
+
+            search_bar_height = ui.next_widget_position().y - search_bar_top;
+            search_matches
+        } else {
+            Arc::default()
+        };
+
+        // when filtering to matches, rows without any matches are filtered, this includes the rows filtered by the renderer
+        // since they are never searched.
+        let search_rows_to_filter = (self.parameters.search_bar
+            && temp_state.search.filter_to_matches
+            && !temp_state.search.query.is_empty())
+        .then_some(search_matches.rows_without_matches.as_slice());
+        let rows_to_filter = search_rows_to_filter.or(renderer_rows_to_filter);
+
+        let row_layout = row_groups.as_ref().map(|row_groups| {
+            temp_state.row_layout.get(
+                data_revision,
+                (&group_inputs, &temp_state.collapsed_groups, rows_to_filter),
+                || {
+                    RowLayout::new(
+                        row_groups,
+                        dimensions.row_count,
+                        row_ordering,
+                        rows_to_filter,
+                        &temp_state.collapsed_groups,
+                    )
+                },
+            )
+        });
+
         // the x/y of this can have negative values if the OUTER scroll area is scrolled right or down, respectively.
         // i.e. if the outer scroll area scrolled down, the y will be negative, above the visible area.
         let outer_next_widget_position = ui.next_widget_position();
@@ -291,9 +876,11 @@ impl<'a, DataSource> DeferredTable<'a, DataSource> {
                 .round_to_pixels(pixels_per_point);
         // FIXME if the parent_max_rect is too small, min_size is not respected, but using
         //       ... `parent_max_rect.size().at_least(self.parameters.min_size)` causes rendering errors
-        let outer_max_rect =
-            Rect::from_min_size(outer_next_widget_position, parent_max_rect.size())
-                .round_to_pixels(pixels_per_point);
+        let outer_max_rect = Rect::from_min_size(
+            outer_next_widget_position,
+            parent_max_rect.size() - Vec2::new(0.0, search_bar_height),
+        )
+        .round_to_pixels(pixels_per_point);
 
         trace!(
             "outer_min_rect: {:?}, outer_max_rect: {:?}",
@@ -307,6 +894,361 @@ impl<'a, DataSource> DeferredTable<'a, DataSource> {
                 .debug_rect(outer_max_rect, Color32::RED, "omxr");
         }
 
+        // the table has the focus after the user presses a pointer button over it, until a button is pressed elsewhere.
+        // keyboard input is ignored while any other widget has the keyboard focus, e.g. a text edit in the search bar.
+        if ctx.input(|i| i.pointer.any_pressed()) {
+            temp_state.focused = ui.rect_contains_pointer(outer_max_rect);
+            // the press could be outside the cells, see `CellPress::update`.
+            temp_state.cell_press = None;
+        }
+        let has_keyboard_focus =
+            temp_state.focused && ctx.memory(|memory| memory.focused().is_none());
+
+        let mut navigation = None;
+        let mut copy = false;
+        let mut history_step = None;
+        let has_history = edit_state
+            .as_ref()
+            .is_some_and(|edit_state| edit_state.history.is_some());
+        if editing && temp_state.focused {
+            // while editing, Escape cancels the edit, Tab/Shift+Tab and Enter/Shift+Enter commit the edit and move the
+            // active cell.  The keys are consumed before the editor is shown, so the editor does not see them.
+            let (cancel, commit_navigation) = ctx.input_mut(|i| {
+                if i.consume_key(Modifiers::NONE, Key::Escape) {
+                    (true, None)
+                } else if i.consume_key(Modifiers::SHIFT, Key::Tab) {
+                    (false, Some(Navigation::Left))
+                } else if i.consume_key(Modifiers::NONE, Key::Tab) {
+                    (false, Some(Navigation::Right))
+                } else if i.consume_key(Modifiers::SHIFT, Key::Enter) {
+                    (false, Some(Navigation::Up))
+                } else if i.consume_key(Modifiers::NONE, Key::Enter) {
+                    (false, Some(Navigation::Down))
+                } else {
+                    (false, None)
+                }
+            });
+
+            if let (Some(editor), Some(edit_state)) = (editor.as_mut(), edit_state.as_mut())
+                && let Some(CellEditState::Editing(cell_index, _, _)) = &edit_state.state
+            {
+                let cell_index = *cell_index;
+                if cancel {
+                    Self::cancel_edit(data_source, cell_index, *editor, edit_state);
+                } else if commit_navigation.is_some() {
+                    Self::apply_edit(data_source, cell_index, *editor, edit_state);
+                    navigation = commit_navigation;
+                }
+
+                if cancel || commit_navigation.is_some() {
+                    // the editor could have the focus, and Tab would otherwise move the focus to the next widget.
+                    ctx.memory_mut(|memory| {
+                        memory.stop_text_input();
+                        memory.move_focus(FocusDirection::None);
+                    });
+                }
+            }
+        } else if has_keyboard_focus {
+            let can_edit = editor.is_some() && temp_state.active_cell.is_some();
+            // the keys are left for other widgets when there is nothing to select.
+            let can_select = self.parameters.selectable_rows
+                || self.parameters.selectable_columns
+                || self.parameters.selectable_cells;
+            let (begin_edit, typed_text, pasted_text) = ctx.input_mut(|i| {
+                if can_select {
+                    if i.consume_key(Modifiers::COMMAND, Key::A) {
+                        selection_action = Some(SelectionAction::SelectAll);
+                    } else if i.consume_key(Modifiers::NONE, Key::Escape) {
+                        selection_action = Some(SelectionAction::Clear);
+                    }
+                }
+                // integrations usually convert Ctrl+C into a copy event
+                copy = i.events.iter().any(|event| matches!(event, Event::Copy))
+                    || i.consume_key(Modifiers::COMMAND, Key::C);
+                navigation = Navigation::consume(i);
+                if has_history {
+                    if i.consume_key(Modifiers::COMMAND | Modifiers::SHIFT, Key::Z)
+                        || i.consume_key(Modifiers::COMMAND, Key::Y)
+                    {
+                        history_step = Some(HistoryStep::Redo);
+                    } else if i.consume_key(Modifiers::COMMAND, Key::Z) {
+                        history_step = Some(HistoryStep::Undo);
+                    }
+                }
+
+                if !can_edit {
+                    return (false, String::new(), None);
+                }
+                let begin_edit = i.consume_key(Modifiers::NONE, Key::Enter)
+                    || i.consume_key(Modifiers::NONE, Key::F2);
+
+                // typing on the active cell starts editing, with the typed text.
+                // the text events are only consumed once an edit has started, not all cells can be edited.
+                let mut typed_text = String::new();
+                // integrations usually convert Ctrl+V into a paste event
+                let mut pasted_text = None;
+                i.events.retain(|event| match event {
+                    Event::Text(text) => {
+                        typed_text.push_str(text);
+                        true
+                    }
+                    Event::Paste(text) => {
+                        pasted_text = Some(text.clone());
+                        false
+                    }
+                    _ => true,
+                });
+
+                (begin_edit, typed_text, pasted_text)
+            });
+
+            if let (Some(editor), Some(edit_state)) = (editor.as_mut(), edit_state.as_mut())
+                && let Some(CellEditState::Pivot(cell_index)) = edit_state.state
+            {
+                if let Some(pasted_text) = pasted_text {
+                    let visible_rows =
+                        visible_indexes(dimensions.row_count, row_ordering, rows_to_filter)
+                            .collect::<Vec<_>>();
+                    let visible_columns = visible_indexes(
+                        dimensions.column_count,
+                        column_ordering,
+                        columns_to_filter,
+                    )
+                    .collect::<Vec<_>>();
+                    let rows = self.parameters.copy_format.parse(&pasted_text);
+
+                    let mut applied = vec![];
+                    let mut rejected = vec![];
+                    let mut originals = vec![];
+                    for (cell_index, text) in
+                        pasted_cells(cell_index, rows, &visible_rows, &visible_columns)
+                    {
+                        // the original value is needed to undo the paste
+                        let original = match edit_state.history.is_some() {
+                            true => editor
+                                .build_item_state(cell_index, data_source)
+                                .map(|(_, value)| value),
+                            false => None,
+                        };
+                        match editor.apply_text(cell_index, &text, data_source) {
+                            Ok(()) => {
+                                applied.push(cell_index);
+                                originals.extend(original.map(|value| (cell_index, value)));
+                            }
+                            Err(ApplyTextError::NotSupported) => rejected
+                                .push((cell_index, self.parameters.strings.paste_not_supported())),
+                            Err(ApplyTextError::Rejected(error)) => {
+                                rejected.push((cell_index, error))
+                            }
+                        }
+                    }
+                    if let Some(history) = edit_state.history.as_mut()
+                        && !originals.is_empty()
+                    {
+                        history.record(HistoryEntry::Cells(originals));
+                    }
+                    if !applied.is_empty() || !rejected.is_empty() {
+                        actions.push(Action::Pasted { applied, rejected });
+                    }
+                } else if !typed_text.is_empty() {
+                    let edit_started = Self::begin_edit_from_text(
+                        data_source,
+                        cell_index,
+                        &typed_text,
+                        *editor,
+                        edit_state,
+                    );
+                    if edit_started {
+                        // the editor must not see the typed text again
+                        ctx.input_mut(|i| {
+                            i.events.retain(|event| !matches!(event, Event::Text(_)))
+                        });
+                    }
+                } else if begin_edit {
+                    Self::begin_edit(data_source, cell_index, *editor, edit_state);
+                }
+            }
+        }
+
+        if copy {
+            let visible_rows = visible_indexes(dimensions.row_count, row_ordering, rows_to_filter)
+                .collect::<Vec<_>>();
+            let visible_columns =
+                visible_indexes(dimensions.column_count, column_ordering, columns_to_filter)
+                    .collect::<Vec<_>>();
+
+            // the selected rows and columns of each selected range
+            let selections: Vec<(BTreeSet<usize>, BTreeSet<usize>)> =
+                if self.parameters.selectable_cells && !temp_state.cell_selections.is_empty() {
+                    temp_state
+                        .cell_selections
+                        .iter()
+                        .map(|range| {
+                            let (rows, columns) =
+                                range.indexes(dimensions, row_ordering, column_ordering);
+                            (rows.into_iter().collect(), columns.into_iter().collect())
+                        })
+                        .collect()
+                } else if self.parameters.selectable_rows && !temp_state.row_selections.is_empty() {
+                    vec![(
+                        temp_state.row_selections.clone(),
+                        visible_columns.iter().copied().collect(),
+                    )]
+                } else if self.parameters.selectable_columns
+                    && !temp_state.column_selections.is_empty()
+                {
+                    vec![(
+                        visible_rows.iter().copied().collect(),
+                        temp_state.column_selections.clone(),
+                    )]
+                } else if let Some(cell_index) = temp_state.active_cell {
+                    vec![(
+                        BTreeSet::from([cell_index.row]),
+                        BTreeSet::from([cell_index.column]),
+                    )]
+                } else {
+                    vec![]
+                };
+
+            let texts = copied_cells(&selections, &visible_rows, &visible_columns)
+                .into_iter()
+                .map(|row| {
+                    row.into_iter()
+                        .map(|cell_index| {
+                            cell_index
+                                .and_then(|cell_index| renderer.cell_text(cell_index, data_source))
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>();
+
+            if texts.iter().flatten().any(Option::is_some) {
+                let rows = texts
+                    .into_iter()
+                    .map(|row| row.into_iter().map(Option::unwrap_or_default).collect())
+                    .collect::<Vec<_>>();
+                let copy_format = self.parameters.copy_format;
+                ctx.copy_text(copy_format.format_text(&rows));
+                if copy_format == CopyFormat::Html {
+                    actions.push(Action::CopiedHtml {
+                        html: copy_format.format(&rows),
+                    });
+                }
+            }
+        }
+
+        if let Some(step) = history_step
+            && let (Some(editor), Some(edit_state)) = (editor.as_mut(), edit_state.as_mut())
+            && let Some(history) = edit_state.history.as_mut()
+            && let Some(entry) = history.pop(step)
+        {
+            let reverted = match entry {
+                HistoryEntry::Cells(cells) => {
+                    // the entry is put back if no cells were reverted, e.g. when the editor doesn't implement `revert`.
+                    let unreverted = HistoryEntry::Cells(
+                        cells
+                            .iter()
+                            .map(|(cell_index, value)| (*cell_index, history.clone_value(value)))
+                            .collect(),
+                    );
+                    let mut inverse = vec![];
+                    for (cell_index, value) in cells {
+                        if let Some(replaced) = editor.revert(cell_index, value, data_source) {
+                            inverse.push((cell_index, replaced));
+                        }
+                    }
+                    if inverse.is_empty() {
+                        history.restore(step, unreverted);
+                        None
+                    } else {
+                        let cell_indexes =
+                            inverse.iter().map(|(cell_index, _)| *cell_index).collect();
+                        Some((
+                            HistoryEntry::Cells(inverse),
+                            HistoryChange::Cells(cell_indexes),
+                        ))
+                    }
+                }
+                HistoryEntry::ColumnOrdering(ordering) => Some((
+                    HistoryEntry::ColumnOrdering(renderer.column_ordering().map(<[usize]>::to_vec)),
+                    HistoryChange::ColumnOrdering(ordering),
+                )),
+                HistoryEntry::RowOrdering(ordering) => Some((
+                    HistoryEntry::RowOrdering(renderer.row_ordering().map(<[usize]>::to_vec)),
+                    HistoryChange::RowOrdering(ordering),
+                )),
+                HistoryEntry::ColumnWidth { column, width } => {
+                    let current_width = state.column_widths.get(column).copied().unwrap_or(width);
+                    if column < state.column_widths.len() {
+                        // change at the end of the frame to avoid cells being the old size.
+                        drag_action = Some(DragAction::SetWidth(column, width));
+                    }
+                    Some((
+                        HistoryEntry::ColumnWidth {
+                            column,
+                            width: current_width,
+                        },
+                        HistoryChange::ColumnWidth { column, width },
+                    ))
+                }
+                HistoryEntry::RowHeight { row, height } => {
+                    let current_height = state.row_heights.get(row).copied().unwrap_or(height);
+                    if row < state.row_heights.len() {
+                        // change at the end of the frame to avoid cells being the old size.
+                        drag_action = Some(DragAction::SetHeight(row, height));
+                    }
+                    Some((
+                        HistoryEntry::RowHeight {
+                            row,
+                            height: current_height,
+                        },
+                        HistoryChange::RowHeight { row, height },
+                    ))
+                }
+            };
+            if let Some((inverse, change)) = reverted {
+                history.push_inverse(step, inverse);
+                actions.push(match step {
+                    HistoryStep::Undo => Action::Undo(change),
+                    HistoryStep::Redo => Action::Redo(change),
+                });
+            }
+        }
+
+        if let Some(navigation) = navigation {
+            let visible_rows = visible_indexes(dimensions.row_count, row_ordering, rows_to_filter)
+                .collect::<Vec<_>>();
+            let visible_columns =
+                visible_indexes(dimensions.column_count, column_ordering, columns_to_filter)
+                    .collect::<Vec<_>>();
+            let page_height = temp_state
+                .last_viewport_rect
+                .map_or(0.0, |viewport_rect| viewport_rect.height() - header_size.y);
+
+            let active_cell = navigation.apply(
+                temp_state.active_cell,
+                &visible_rows,
+                &visible_columns,
+                |row| {
+                    state.row_heights.get(row).unwrap_or(&inner_cell_size.y)
+                        + (outer_cell_size.y - inner_cell_size.y)
+                        + 1.0
+                },
+                page_height,
+            );
+            if let Some(cell_index) = active_cell {
+                temp_state.active_cell = Some(cell_index);
+                temp_state.scroll_to = Some(cell_index);
+                if let Some(edit_state) = edit_state.as_mut() {
+                    edit_state.state = Some(CellEditState::Pivot(cell_index));
+                }
+                if self.parameters.selectable_cells {
+                    temp_state.cell_selections = vec![CellRange::single(cell_index)];
+                    request_cell_selection_changed_action = true;
+                }
+            }
+        }
+
         ui.scope_builder(UiBuilder::new().max_rect(outer_max_rect), |ui|{
 
             ui.style_mut().spacing.scroll = egui::style::ScrollStyle::solid();
@@ -322,20 +1264,13 @@ impl<'a, DataSource> DeferredTable<'a, DataSource> {
             if state.column_widths.len() < dimensions.column_count {
                 // Note: We do not truncate the column widths, so that if a data source has `n` columns, then later `< n` columns
                 //       then later again `>= n` columns, the previously used columns widths still apply.
+                let previous_column_count = state.column_widths.len();
                 state.column_widths.resize(dimensions.column_count, inner_cell_size.x);
 
-                // apply default widths
-                if let Some(column_parameters) = self.parameters.column_parameters {
-                    column_parameters.iter().enumerate().for_each(|(index, column)| {
-                        if let Some(default_width) = column.default_dimension {
-                            let sanitized_width = if column.resizable {
-                                column.dimension_range.clamp(default_width)
-                            } else {
-                                default_width
-                            };
-                            state.column_widths[index] = sanitized_width;
-                        }
-                    });
+                // apply default widths, only to the new columns so that widths that were changed by the user, or set via
+                // `DeferredTableState::set_column_widths`, are retained.
+                for index in previous_column_count..dimensions.column_count {
+                    state.column_widths[index] = Self::default_dimension(self.parameters.column_parameters, index, inner_cell_size.x);
                 }
             }
 
@@ -343,20 +1278,12 @@ impl<'a, DataSource> DeferredTable<'a, DataSource> {
             if state.row_heights.len() < dimensions.row_count {
                 // Note: We do not truncate the row heights, so that if a data source has `n` rows, then later `< n` rows
                 //       then later again `>= n` rows, the previously used rows heights still apply.
+                let previous_row_count = state.row_heights.len();
                 state.row_heights.resize(dimensions.row_count, inner_cell_size.y);
 
-                // apply default heights
-                if let Some(row_parameters) = self.parameters.row_parameters {
-                    row_parameters.iter().enumerate().for_each(|(index, row)| {
-                        if let Some(default_height) = row.default_dimension {
-                            let sanitized_width = if row.resizable {
-                                row.dimension_range.clamp(default_height)
-                            } else {
-                                default_height
-                            };
-                            state.row_heights[index] = sanitized_width;
-                        }
-                    });
+                // apply default heights, only to the new rows, see above.
+                for index in previous_row_count..dimensions.row_count {
+                    state.row_heights[index] = Self::default_dimension(self.parameters.row_parameters, index, inner_cell_size.y);
                 }
             }
 
@@ -382,43 +1309,59 @@ impl<'a, DataSource> DeferredTable<'a, DataSource> {
                 MappedColumn(usize),
                 MappedRow(usize),
                 Cell(CellIndex),
+                /// the index of the group in the column groups.
+                ColumnGroup(usize),
             }
 
             //
             // container for the table and the scroll bars.
             //
 
-            let column_ordering = renderer.column_ordering().unwrap_or_default();
-            let row_ordering = renderer.row_ordering().unwrap_or_default();
-
             let outer_inner_difference = outer_cell_size - inner_cell_size;
             // pre-calculate to avoid doing the divide for every cell.
             let outer_inner_half_difference = outer_inner_difference / 2.0;
 
+            // the rows are laid out with the group header rows, which are as tall as the column headers, see `RowLayout`.
+            let (layout_row_count, layout_row_ordering, layout_rows_to_filter) = match &row_layout {
+                Some(row_layout) => (row_layout.count(), row_layout.ordering.as_slice(), Some(row_layout.filter.as_slice())),
+                None => (dimensions.row_count, row_ordering, rows_to_filter),
+            };
+            let row_height = |index: usize| match index < dimensions.row_count {
+                true => state.row_heights[index],
+                false => inner_cell_size.y,
+            };
+            let row_size = |index: usize| {
+                (!layout_rows_to_filter.is_some_and(|rows| rows.contains(&index))).then(|| row_height(index) + outer_inner_difference.y + 1.0)
+            };
+            // returns the index, the visible row index and the row of the layout at the visible index of the layout.
+            let layout_row = |visible_index: usize| {
+                let index = map_index(layout_row_count, layout_row_ordering, visible_index);
+                match &row_layout {
+                    Some(row_layout) => (index, row_layout.visible_row_index(visible_index), row_layout.row(index)),
+                    None => (index, visible_index, LayoutRow::Row(index)),
+                }
+            };
+
             // add the width/height of the column/row headers to the sum of the column widths/row heights, respectively,
             // while ignoring widths/heights that don't apply to the current dimensions.
             let total_content_width = state.column_widths
                 .iter()
                 .take(dimensions.column_count)
                 .sum::<f32>() + ((outer_inner_difference.x + 1.0) * dimensions.column_count as f32) + outer_cell_size.x;
-            let total_content_height = state.row_heights
-                .iter()
-                .take(dimensions.row_count)
-                .sum::<f32>() + ((outer_inner_difference.y + 1.0) * dimensions.row_count as f32) + outer_cell_size.y;
+            let total_content_height = (0..layout_row_count)
+                .map(row_height)
+                .sum::<f32>() + ((outer_inner_difference.y + 1.0) * layout_row_count as f32) + header_size.y;
 
-            let columns_to_filter = renderer.columns_to_filter();
+            // Note: the filtered indexes are already mapped indexes, filtering applies AFTER mapping.
             let filtered_content_width = columns_to_filter.map_or(0.0,|columns|{
-                columns.iter().take(dimensions.column_count).map(|index| {
-                    let mapped_index = Self::map_index(dimensions.column_count, column_ordering, *index);
-                    state.column_widths.get(mapped_index).map(|it|it + outer_inner_difference.x + 1.0).unwrap_or(0.0)
+                columns.iter().take(dimensions.column_count).map(|mapped_index| {
+                    state.column_widths.get(*mapped_index).map(|it|it + outer_inner_difference.x + 1.0).unwrap_or(0.0)
                 }).sum::<f32>()
             });
 
-            let rows_to_filter = renderer.rows_to_filter();
-            let filtered_content_height = rows_to_filter.map_or(0.0,|rows|{
-                rows.iter().take(dimensions.row_count).map(|index| {
-                    let mapped_index = Self::map_index(dimensions.column_count, column_ordering, *index);
-                    state.row_heights.get(mapped_index).map(|it|it + outer_inner_difference.y + 1.0).unwrap_or(0.0)
+            let filtered_content_height = layout_rows_to_filter.map_or(0.0,|rows|{
+                rows.iter().take(layout_row_count).filter(|mapped_index| **mapped_index < layout_row_count).map(|mapped_index| {
+                    row_height(*mapped_index) + outer_inner_difference.y + 1.0
                 }).sum::<f32>()
             });
 
@@ -426,6 +1369,10 @@ impl<'a, DataSource> DeferredTable<'a, DataSource> {
                 total_content_width - filtered_content_width,
                 total_content_height - filtered_content_height,
             );
+
+            // the widths/heights of the bands of pinned columns/rows, which are not scrolled, see `Pinning`.
+            let (pinned_start_width, pinned_end_width) = column_pinning.band_sizes(Self::size_of_index(&state.column_widths, columns_to_filter, outer_inner_difference.x + 1.0));
+            let (pinned_start_height, pinned_end_height) = row_pinning.band_sizes(row_size);
             trace!("total_content_size: {:?}, filtered_content_width: {}, filtered_content_height: {}", total_content_size, filtered_content_width, filtered_content_height);
 
             ui.scope_builder(UiBuilder::new().max_rect(inner_max_rect), |ui|{
@@ -459,9 +1406,30 @@ impl<'a, DataSource> DeferredTable<'a, DataSource> {
 
                 total_content_size.x += additional_width;
 
-                egui::ScrollArea::both()
+                let mut scroll_area = egui::ScrollArea::both()
                     .id_salt("table_scroll_area")
-                    .scroll_bar_visibility(ScrollBarVisibility::AlwaysVisible)
+                    .scroll_bar_visibility(ScrollBarVisibility::AlwaysVisible);
+
+                // the viewport from the previous frame is needed so that we only scroll if the cell is not already visible.
+                if let Some(viewport_rect) = temp_state.last_viewport_rect
+                    && let Some(cell_index) = temp_state.scroll_to.take() {
+                    let cells_viewport_size = viewport_rect.size() - header_size;
+                    // pinned columns/rows are always visible, the others must be visible between the pinned bands.
+                    let scroll_offset = Vec2::new(
+                        match column_pinning.pin(cell_index.column) {
+                            Some(_) => viewport_rect.min.x,
+                            None => Self::scroll_offset_for_index(cell_index.column, dimensions.column_count, column_ordering, Self::size_of_index(&state.column_widths, columns_to_filter, outer_inner_difference.x + 1.0), viewport_rect.min.x + pinned_start_width..viewport_rect.min.x + cells_viewport_size.x - pinned_end_width) - pinned_start_width,
+                        },
+                        match row_pinning.pin(cell_index.row) {
+                            Some(_) => viewport_rect.min.y,
+                            None => Self::scroll_offset_for_index(cell_index.row, layout_row_count, layout_row_ordering, row_size, viewport_rect.min.y + pinned_start_height..viewport_rect.min.y + cells_viewport_size.y - pinned_end_height) - pinned_start_height,
+                        },
+                    );
+                    trace!("scrolling to cell: {:?}, scroll_offset: {:?}", cell_index, scroll_offset);
+                    scroll_area = scroll_area.scroll_offset(scroll_offset);
+                }
+
+                scroll_area
                     .show_viewport(ui, |ui, viewport_rect| {
                         let viewport_changed = temp_state.last_viewport_rect.map_or(false, |last_viewport_rect| {
                             last_viewport_rect != viewport_rect
@@ -472,10 +1440,10 @@ impl<'a, DataSource> DeferredTable<'a, DataSource> {
                         trace!("max_rect: {:?}, viewport_rect: {:?}", ui.max_rect(), viewport_rect);
                         //ui.painter().debug_rect(ui.max_rect(), Color32::RED, "mr");
                         let translated_viewport_rect = viewport_rect.translate(ui.max_rect().min.to_vec2());
-                        let cells_viewport_rect = Rect::from_min_max(viewport_rect.min, viewport_rect.max - outer_cell_size);
+                        let cells_viewport_rect = Rect::from_min_max(viewport_rect.min, viewport_rect.max - header_size);
                         if false {
                             ui.ctx().debug_painter().debug_rect(translated_viewport_rect, Color32::GREEN, "vr");
-                            ui.ctx().debug_painter().debug_rect(cells_viewport_rect.translate(ui.max_rect().min.to_vec2()).translate(outer_cell_size), Color32::RED, "tvr");
+                            ui.ctx().debug_painter().debug_rect(cells_viewport_rect.translate(ui.max_rect().min.to_vec2()).translate(header_size), Color32::RED, "tvr");
                         }
 
                         ui.set_height(total_content_size.y);
@@ -483,39 +1451,35 @@ impl<'a, DataSource> DeferredTable<'a, DataSource> {
 
                         //ui.ctx().debug_painter().debug_rect(ui.max_rect(), Color32::RED, "mr");
 
-                        fn range_and_index_for_offset(offset: f32, values: &[f32], map: &[usize], filter: &Option<&[usize]>, sizing: f32) -> Result<(Range<f32>, usize, usize, usize), ()> {
+                        fn range_and_index_for_offset(offset: f32, count: usize, map: &[usize], size: impl Fn(usize) -> Option<f32>) -> Result<(Range<f32>, usize, usize, usize), ()> {
                             let mut visible_index = 0;
                             let mut min = 0.0;
                             let mut max = 0.0;
                             let mut filtered = 0;
                             let mut index ;
-                            let values_len = values.len();
                             loop {
                                 index = *map.get(visible_index).unwrap_or(&visible_index);
-                                if index >= values_len {
+                                if index >= count {
                                     // handle out-of-range mapping values
                                     index = visible_index;
                                 }
 
-                                let Some(value) = values.get(index) else {
+                                if visible_index >= count {
                                     if visible_index == 0 {
                                         // no values at all
                                         return Err(())
                                     }
                                     // no more values, use previous loop iteration values
                                     break
-                                };
+                                }
 
                                 // filter applies AFTER mapping
-                                if let Some(filter) = filter {
-                                    if filter.contains(&index) {
-                                        visible_index += 1;
-                                        filtered += 1;
-                                        continue;
-                                    }
-                                }
+                                let Some(size) = size(index) else {
+                                    visible_index += 1;
+                                    filtered += 1;
+                                    continue;
+                                };
 
-                                let size = value + sizing;
                                 max += size;
 
                                 if offset >= min && offset < max {
@@ -529,13 +1493,14 @@ impl<'a, DataSource> DeferredTable<'a, DataSource> {
                             Ok((min..max, index, visible_index, filtered))
                         }
 
-                        // use the cells_viewport_rect for upper left and origin calculation
-                        let (first_column, first_column_index, first_column_visible_index, first_column_filtered_count) = range_and_index_for_offset(cells_viewport_rect.min.x, &state.column_widths, &column_ordering, &columns_to_filter, outer_inner_difference.x + 1.0).unwrap();
-                        let (first_row, first_row_index, first_row_visible_index, first_row_filtered_count) = range_and_index_for_offset(cells_viewport_rect.min.y, &state.row_heights, &row_ordering, &rows_to_filter, outer_inner_difference.y + 1.0).unwrap();
+                        // use the cells_viewport_rect for upper left and origin calculation, the pinned columns/rows are first
+                        // in the ordering and the scrolled columns/rows start after the pinned-start band.
+                        let (first_column, first_column_index, first_column_visible_index, first_column_filtered_count) = range_and_index_for_offset(cells_viewport_rect.min.x + pinned_start_width, state.column_widths.len(), column_ordering, Self::size_of_index(&state.column_widths, columns_to_filter, outer_inner_difference.x + 1.0)).unwrap();
+                        let (first_row, first_row_index, first_row_visible_index, first_row_filtered_count) = range_and_index_for_offset(cells_viewport_rect.min.y + pinned_start_height, layout_row_count, layout_row_ordering, row_size).unwrap();
 
                         // use the total viewport (including header area) to find the last column and row
-                        let (last_column, _last_column_index, last_column_visible_index, last_column_filtered_count) = range_and_index_for_offset(viewport_rect.max.x, &state.column_widths, &column_ordering, &columns_to_filter, outer_inner_difference.x + 1.0).unwrap();
-                        let (last_row, _last_row_index, last_row_visible_index, last_row_filtered_count) = range_and_index_for_offset(viewport_rect.max.y, &state.row_heights, &row_ordering, &rows_to_filter, outer_inner_difference.y + 1.0).unwrap();
+                        let (last_column, _last_column_index, last_column_visible_index, last_column_filtered_count) = range_and_index_for_offset(viewport_rect.max.x - pinned_end_width, state.column_widths.len(), column_ordering, Self::size_of_index(&state.column_widths, columns_to_filter, outer_inner_difference.x + 1.0)).unwrap();
+                        let (last_row, _last_row_index, last_row_visible_index, last_row_filtered_count) = range_and_index_for_offset(viewport_rect.max.y - pinned_end_height, layout_row_count, layout_row_ordering, row_size).unwrap();
 
                         // note, if the scroll area doesn't line up exactly with the viewport, then we may have to render additional rows/columns that
                         // are outside of this rect
@@ -569,28 +1534,133 @@ impl<'a, DataSource> DeferredTable<'a, DataSource> {
 
                         let mut row_counter = cell_origin.row - first_row_filtered_count;
 
+                        let (primary_pressed, primary_down, modifiers) = ui.input(|i| (i.pointer.primary_pressed(), i.pointer.primary_down(), i.modifiers));
+                        if !primary_down {
+                            temp_state.selecting_cells = false;
+                            temp_state.selecting_rows = None;
+                        }
+
+                        let cells_clip_rect = Rect::from_min_max((table_max_rect.min + header_size) + Vec2::splat(1.0), translated_viewport_rect.max).intersect(parent_clip_rect);
+
+                        // when dragging, the pointer is clamped to the cells so that the selection follows the pointer
+                        // even when the pointer is outside the cells, e.g. while auto-scrolling.
+                        let selection_pointer_pos = pointer_interact_pos.clamp(cells_clip_rect.min, cells_clip_rect.max - Vec2::splat(1.0));
+                        let row_selection_pointer_y = selection_pointer_pos.y;
+
+                        // the pinned-start bands are after the headers, the pinned-end bands are at the end of the viewport, or at
+                        // the end of the content if it's smaller than the viewport.
+                        let pinned_start_x = table_max_rect.min.x + outer_cell_size.x + 1.0;
+                        let pinned_start_y = table_max_rect.min.y + header_size.y + 1.0;
+                        let pinned_end_x = (ui.max_rect().min.x + total_content_size.x + 1.0).min(translated_viewport_rect.max.x) - pinned_end_width;
+                        let pinned_end_y = (ui.max_rect().min.y + total_content_size.y + 1.0).min(translated_viewport_rect.max.y) - pinned_end_height;
+
+                        let column_slots = column_pinning.grid_slots(
+                            dimensions.column_count,
+                            column_ordering,
+                            cell_origin.column..cell_origin.column + visible_column_count,
+                            Self::size_of_index(&state.column_widths, columns_to_filter, outer_inner_difference.x + 1.0),
+                            pinned_start_x,
+                            pinned_end_x,
+                            Rangef::new(pinned_start_x + pinned_start_width, pinned_end_x),
+                        );
+                        let row_slots = row_pinning.grid_slots(
+                            layout_row_count,
+                            layout_row_ordering,
+                            cell_origin.row..cell_origin.row + visible_row_count,
+                            row_size,
+                            pinned_start_y,
+                            pinned_end_y,
+                            Rangef::new(pinned_start_y + pinned_start_height, pinned_end_y),
+                        );
+
+                        // the group header rows that are shown, as the index of the group and the header rect, the header of the group
+                        // of the first shown row is kept at the top of the scrolled rows, until the next header pushes it up.
+                        let scrolled_rows_clip = Rangef::new(pinned_start_y + pinned_start_height, pinned_end_y);
+                        let mut shown_group_headers: Vec<(usize, Rect)> = vec![];
+                        let mut sticky_group_header = None;
+                        if let Some(row_groups) = &row_groups {
+                            let group_header_height = outer_cell_size.y + 1.0;
+                            let x_range = table_max_rect.min.x..=cells_clip_rect.max.x;
+                            let mut first_group = None;
+                            let mut y = rect.min.y + header_size.y + 1.0;
+                            for row_slot in row_slots.iter().skip(1).filter(|row_slot| row_slot.position.is_none()) {
+                                let (layout_index, _, layout_row) = layout_row(row_slot.visible_index);
+                                let Some(size) = row_size(layout_index) else {
+                                    continue;
+                                };
+                                let group_index = match layout_row {
+                                    LayoutRow::Row(mapped_row_index) => row_groups.group_of(mapped_row_index),
+                                    LayoutRow::GroupHeader(group_index) => {
+                                        shown_group_headers.push((group_index, Rect::from_x_y_ranges(x_range.clone(), y..=y + outer_cell_size.y)));
+                                        Some(group_index)
+                                    }
+                                };
+                                if first_group.is_none() {
+                                    first_group = group_index;
+                                }
+                                y += size;
+                            }
+
+                            sticky_group_header = first_group
+                                .filter(|group_index| !shown_group_headers.iter().any(|(shown_group_index, header_rect)| {
+                                    shown_group_index == group_index && header_rect.min.y >= scrolled_rows_clip.min
+                                }))
+                                .map(|group_index| {
+                                    let next_header_y = shown_group_headers
+                                        .iter()
+                                        .find(|(shown_group_index, _)| *shown_group_index > group_index)
+                                        .map_or(f32::INFINITY, |(_, header_rect)| header_rect.min.y - group_header_height);
+                                    let y = scrolled_rows_clip.min.min(next_header_y);
+                                    (group_index, Rect::from_x_y_ranges(x_range.clone(), y..=y + outer_cell_size.y))
+                                });
+
+                            table_height += shown_group_headers
+                                .iter()
+                                .map(|(_, header_rect)| header_rect.y_range().intersection(scrolled_rows_clip).span().max(0.0))
+                                .sum::<f32>();
+                        }
+
+                        // columns/rows can only be dropped onto columns/rows with the same pin.
+                        let can_drop = |payload: CellId, cell_id: CellId| match (payload, cell_id) {
+                            (CellId::MappedColumn(from), CellId::MappedColumn(to)) => column_pinning.pin(from) == column_pinning.pin(to),
+                            (CellId::MappedRow(from), CellId::MappedRow(to)) => row_pinning.pin(from) == row_pinning.pin(to),
+                            (CellId::ColumnGroup(group), CellId::MappedColumn(to)) => self.parameters.column_groups[group].columns
+                                .iter()
+                                .all(|column| *column != to && column_pinning.pin(*column) == column_pinning.pin(to)),
+                            _ => false,
+                        };
+
+                        // the column headers that are shown, as visible index, mapped index, cell rect and cell clip rect,
+                        // used to show the column groups above them.
+                        let mut shown_column_headers = vec![];
+
                         trace!("headers");
                         let header_row_bg_color = ui.style().visuals.widgets.inactive.bg_fill.gamma_multiply(0.5);
                         let mut accumulated_row_heights = 0.0;
-                        for grid_row_index in 0..=visible_row_count {
-                            if grid_row_index + cell_origin.row > dimensions.row_count {
-                                trace!("break 1");
-                                break
-                            }
-
-                            let visible_row_index = cell_origin.row + (grid_row_index.saturating_sub(1));
-                            let mapped_row_index = Self::map_index(dimensions.row_count, row_ordering, visible_row_index);
+                        for row_slot in &row_slots {
+                            let grid_row_index = row_slot.grid_index;
+                            let (layout_index, visible_row_index, layout_row) = layout_row(row_slot.visible_index);
 
                             let row_kind = Self::build_row_kind(grid_row_index);
 
                             if matches!(row_kind, RowKind::ValuesRow) {
-                                if let Some(rows_to_filter) = &rows_to_filter {
-                                    if rows_to_filter.contains(&(mapped_row_index)) {
+                                if let Some(rows_to_filter) = &layout_rows_to_filter {
+                                    if rows_to_filter.contains(&(layout_index)) {
                                         trace!("filtered row");
                                         continue;
                                     }
                                 }
                             }
+                            let mapped_row_index = match layout_row {
+                                LayoutRow::Row(mapped_row_index) => mapped_row_index,
+                                // the group header rows are shown after the cells.
+                                LayoutRow::GroupHeader(_) if matches!(row_kind, RowKind::ValuesRow) => {
+                                    accumulated_row_heights += outer_cell_size.y + 1.0;
+                                    row_counter += 1;
+                                    continue;
+                                }
+                                LayoutRow::GroupHeader(_) => map_index(dimensions.row_count, row_ordering, visible_row_index),
+                            };
                             row_counter += 1;
 
                             let row_was_selected = if matches!(row_kind, RowKind::ValuesRow) && self.parameters.selectable_rows {
@@ -612,11 +1682,8 @@ impl<'a, DataSource> DeferredTable<'a, DataSource> {
                             // Prevent applying expansion twice.
                             let mut expansion_applied = false;
 
-                            for grid_column_index in 0..=visible_column_count {
-                                if grid_column_index + cell_origin.column > dimensions.column_count {
-                                    break
-                                }
-
+                            for column_slot in &column_slots {
+                                let grid_column_index = column_slot.grid_index;
                                 let cell_kind = Self::build_cell_kind(grid_row_index, grid_column_index);
 
                                 if matches!(cell_kind, CellKind::Value) {
@@ -625,8 +1692,8 @@ impl<'a, DataSource> DeferredTable<'a, DataSource> {
                                     break
                                 }
 
-                                let visible_column_index = cell_origin.column + (grid_column_index.saturating_sub(1));
-                                let mapped_column_index = Self::map_index(dimensions.column_count, column_ordering, visible_column_index);
+                                let visible_column_index = column_slot.visible_index;
+                                let mapped_column_index = map_index(dimensions.column_count, column_ordering, visible_column_index);
 
                                 if matches!(cell_kind, CellKind::ColumnHeader) {
                                     if let Some(columns_to_filter) = &columns_to_filter {
@@ -647,7 +1714,8 @@ impl<'a, DataSource> DeferredTable<'a, DataSource> {
 
                                 let inner_column_width = if matches!(cell_kind, CellKind::ColumnHeader) {
                                     let mut width = state.column_widths[mapped_column_index];
-                                    if !expansion_applied {
+                                    // pinned columns are not expanded
+                                    if !expansion_applied && column_slot.position.is_none() {
                                         if let Some(column_parameters) = self.parameters.column_parameters {
                                             match column_parameters.get(mapped_column_index) {
                                                 Some(params) if params.expandable => {
@@ -665,30 +1733,61 @@ impl<'a, DataSource> DeferredTable<'a, DataSource> {
 
                                 let outer_column_width = inner_column_width + outer_inner_difference.x;
 
-                                let mut y = start_pos.y + accumulated_row_heights;
-                                let mut x = start_pos.x + accumulated_column_widths;
+                                let mut y = row_slot.position.unwrap_or(start_pos.y + accumulated_row_heights);
+                                let mut x = column_slot.position.unwrap_or(start_pos.x + accumulated_column_widths);
                                 accumulated_column_widths += outer_column_width + 1.0;
 
-                                if matches!(cell_kind, CellKind::Corner | CellKind::ColumnHeader) {
+                                if matches!(cell_kind, CellKind::Corner) {
                                     y = table_max_rect.min.y;
                                 }
+                                if matches!(cell_kind, CellKind::ColumnHeader) {
+                                    // below the column group rows
+                                    y = table_max_rect.min.y + column_groups_height;
+                                }
                                 if matches!(cell_kind, CellKind::Corner | CellKind::RowHeader) {
                                     x = table_max_rect.min.x;
                                 }
 
-                                let cell_rect = Rect::from_min_size(Pos2::new(x, y), (outer_column_width, outer_row_height).into());
-
-                                let mut cell_clip_rect = cell_rect.intersect(translated_viewport_rect);
+                                // the corner also spans the column group rows
+                                let cell_height = match cell_kind {
+                                    CellKind::Corner => header_size.y,
+                                    _ => outer_row_height,
+                                };
+                                let cell_rect = Rect::from_min_size(Pos2::new(x, y), (outer_column_width, cell_height).into());
+
+                                let cell_clip_rect = cell_rect
+                                    .intersect(translated_viewport_rect)
+                                    .intersect(Rect::from_x_y_ranges(column_slot.clip, row_slot.clip))
+                                    .intersect(parent_clip_rect);
+
+                                let mut cell_inner_rect = cell_rect.shrink2(outer_inner_half_difference);
+
+                                let header_column_parameters = matches!(cell_kind, CellKind::ColumnHeader).then(|| {
+                                    self.parameters.column_parameters
+                                        .and_then(|it| it.get(mapped_column_index))
+                                        .unwrap_or(&default_column_parameters)
+                                });
+                                let column_sortable = self.parameters.sortable_columns && header_column_parameters.is_some_and(|it| it.sortable);
+                                let column_filter_kind = header_column_parameters.and_then(|it| it.filter.as_ref());
+
+                                // reserve space for the sort indicator, to the right of the label
+                                let sort_indicator_rect = if column_sortable {
+                                    let (label_rect, sort_indicator_rect) = cell_inner_rect.split_left_right_at_x(cell_inner_rect.max.x - cell_inner_rect.height());
+                                    cell_inner_rect = label_rect;
+                                    Some(sort_indicator_rect)
+                                } else {
+                                    None
+                                };
 
-                                if grid_row_index == 1 {
-                                    cell_clip_rect.min.y = table_max_rect.min.y + outer_cell_size.y + 1.0;
-                                }
-                                if grid_column_index == 1 {
-                                    cell_clip_rect.min.x = table_max_rect.min.x + outer_cell_size.x + 1.0;
-                                }
-                                let cell_clip_rect = cell_clip_rect.intersect(parent_clip_rect);
+                                // reserve space for the filter button, between the label and the sort indicator
+                                let filter_button_rect = if column_filter_kind.is_some() {
+                                    let (label_rect, filter_button_rect) = cell_inner_rect.split_left_right_at_x(cell_inner_rect.max.x - cell_inner_rect.height());
+                                    cell_inner_rect = label_rect;
+                                    Some(filter_button_rect)
+                                } else {
+                                    None
+                                };
 
-                                let cell_inner_rect = cell_rect.shrink2(outer_inner_half_difference);
                                 let cell_inner_clip_rect = cell_inner_rect.intersect(cell_clip_rect);
 
                                 if false {
@@ -708,7 +1807,17 @@ impl<'a, DataSource> DeferredTable<'a, DataSource> {
                                     continue;
                                 }
 
-                                let bg_color = if grid_row_index == 0 {
+                                if matches!(cell_kind, CellKind::ColumnHeader) {
+                                    shown_column_headers.push((visible_column_index, mapped_column_index, cell_rect, cell_clip_rect));
+                                }
+
+                                let column_was_selected = matches!(cell_kind, CellKind::ColumnHeader)
+                                    && self.parameters.selectable_columns
+                                    && temp_state.column_selections.contains(&mapped_column_index);
+
+                                let bg_color = if column_was_selected {
+                                    ui.style().visuals.selection.bg_fill
+                                } else if grid_row_index == 0 {
                                     header_row_bg_color
                                 } else {
                                     row_bg_color
@@ -765,15 +1874,18 @@ impl<'a, DataSource> DeferredTable<'a, DataSource> {
                                     // if column_parameters.resizable && !column_parameters.expandable {
                                     if column_parameters.resizable {
                                         if resize_response.drag_started_by(PointerButton::Primary) && temp_state.drag_state.is_none() {
-                                            temp_state.drag_state = pointer_pos.map(|start_pos| DragState { index: mapped_column_index, start_pos, cell_kind: cell_kind, initial_size: outer_column_width });
+                                            temp_state.drag_state = pointer_pos.map(|start_pos| DragState { index: mapped_column_index, start_pos, cell_kind: cell_kind, initial_size: outer_column_width, initial_inner_size: state.column_widths[mapped_column_index] });
                                         }
 
                                         if resize_response.drag_stopped() {
                                             clear_drag_state = true;
+                                            if let Some(drag_state) = temp_state.drag_state.filter(|it| it.index == mapped_column_index && it.cell_kind == cell_kind) {
+                                                resize_entries.push(HistoryEntry::ColumnWidth { column: mapped_column_index, width: drag_state.initial_inner_size });
+                                            }
                                         }
 
                                         match temp_state.drag_state {
-                                            Some(DragState { index, start_pos, cell_kind: drag_cell_kind, initial_size }) if index == mapped_column_index && drag_cell_kind == cell_kind => {
+                                            Some(DragState { index, start_pos, cell_kind: drag_cell_kind, initial_size, .. }) if index == mapped_column_index && drag_cell_kind == cell_kind => {
                                                 // dragging this column
                                                 let drag_delta = pointer_pos.map_or(Vec2::ZERO, |current_pos| current_pos - start_pos);
                                                 let new_outer_column_width = initial_size + drag_delta.x;
@@ -815,15 +1927,18 @@ impl<'a, DataSource> DeferredTable<'a, DataSource> {
                                     };
 
                                     if resize_response.drag_started_by(PointerButton::Primary) && temp_state.drag_state.is_none() {
-                                        temp_state.drag_state = pointer_pos.map(|start_pos|DragState { index: mapped_row_index, start_pos, cell_kind: cell_kind, initial_size: outer_row_height });
+                                        temp_state.drag_state = pointer_pos.map(|start_pos|DragState { index: mapped_row_index, start_pos, cell_kind: cell_kind, initial_size: outer_row_height, initial_inner_size: inner_row_height });
                                     }
 
                                     if resize_response.drag_stopped() {
                                         clear_drag_state = true;
+                                        if let Some(drag_state) = temp_state.drag_state.filter(|it| it.index == mapped_row_index && it.cell_kind == cell_kind) {
+                                            resize_entries.push(HistoryEntry::RowHeight { row: mapped_row_index, height: drag_state.initial_inner_size });
+                                        }
                                     }
 
                                     match temp_state.drag_state {
-                                        Some(DragState { index, start_pos, cell_kind: drag_cell_kind, initial_size }) if index == mapped_row_index && drag_cell_kind == cell_kind => {
+                                        Some(DragState { index, start_pos, cell_kind: drag_cell_kind, initial_size, .. }) if index == mapped_row_index && drag_cell_kind == cell_kind => {
                                             // dragging this row
                                             let drag_delta = pointer_pos.map_or(Vec2::ZERO, |current_pos| current_pos - start_pos);
                                             let new_outer_row_height = initial_size + drag_delta.y;
@@ -863,10 +1978,43 @@ impl<'a, DataSource> DeferredTable<'a, DataSource> {
                                     CellKind::Value => unreachable!(),
                                 };
 
-                                if matches!(cell_kind, CellKind::ColumnHeader | CellKind::RowHeader) {
+                                // when enabled, dragging an unselected row header selects rows, dragging a selected row header re-orders it.
+                                let row_drag_selects = matches!(cell_kind, CellKind::RowHeader)
+                                    && self.parameters.selectable_rows
+                                    && self.parameters.drag_select_rows
+                                    && (temp_state.selecting_rows.is_some() || !row_was_selected);
+
+                                if matches!(cell_kind, CellKind::ColumnHeader | CellKind::RowHeader) && !row_drag_selects {
                                     response.dnd_set_drag_payload(cell_id);
                                 }
 
+                                if row_drag_selects {
+                                    if response.drag_started_by(PointerButton::Primary) && temp_state.drag_state.is_none() {
+                                        temp_state.row_selection_anchor = apply_axis_selection(
+                                            &mut temp_state.row_selections,
+                                            temp_state.row_selection_anchor,
+                                            mapped_row_index,
+                                            modifiers,
+                                            visible_indexes(dimensions.row_count, row_ordering, rows_to_filter),
+                                        );
+                                        temp_state.selecting_rows = Some(mapped_row_index);
+                                        request_row_selection_changed_action = true;
+                                    } else if temp_state.selecting_rows.is_some_and(|selecting_row| selecting_row != mapped_row_index)
+                                        && cell_clip_rect.y_range().contains(row_selection_pointer_y)
+                                    {
+                                        // extend the selection from the anchor to the row under the pointer
+                                        temp_state.row_selection_anchor = apply_axis_selection(
+                                            &mut temp_state.row_selections,
+                                            temp_state.row_selection_anchor,
+                                            mapped_row_index,
+                                            modifiers | Modifiers::SHIFT,
+                                            visible_indexes(dimensions.row_count, row_ordering, rows_to_filter),
+                                        );
+                                        temp_state.selecting_rows = Some(mapped_row_index);
+                                        request_row_selection_changed_action = true;
+                                    }
+                                }
+
                                 let mut cell_ui = ui.new_child(UiBuilder::new()
                                     .id_salt(cell_id)
                                     .max_rect(cell_inner_rect));
@@ -931,37 +2079,215 @@ impl<'a, DataSource> DeferredTable<'a, DataSource> {
                                     });
                                 }
 
+                                if matches!(cell_kind, CellKind::ColumnHeader) {
+                                    let reserved_width = [sort_indicator_rect, filter_button_rect]
+                                        .iter()
+                                        .flatten()
+                                        .map(Rect::width)
+                                        .sum::<f32>();
+                                    Self::measure_content_width(&mut content_widths, mapped_column_index, cell_ui.min_rect().width() + reserved_width);
+                                }
+
+                                let mut sort_direction = temp_state.sort.direction(mapped_column_index);
+                                // when the columns are also selectable, only the sort indicator area sorts, so preview the indicator when hovered.
+                                if self.parameters.selectable_columns
+                                    && sort_direction.is_none()
+                                    && sort_indicator_rect.is_some_and(|rect| ui.rect_contains_pointer(rect.intersect(cell_clip_rect)))
+                                {
+                                    sort_direction = Some(SortDirection::Ascending);
+                                }
+                                if let (Some(sort_indicator_rect), Some(direction)) = (sort_indicator_rect, sort_direction) {
+                                    // only show the priority when sorting by multiple columns
+                                    let priority = temp_state.sort.priority(mapped_column_index)
+                                        .filter(|_| temp_state.sort.len() > 1);
+
+                                    let sort_indicator_painter = ui.painter()
+                                        .with_clip_rect(sort_indicator_rect.intersect(cell_clip_rect));
+                                    Self::paint_sort_indicator(ui, &sort_indicator_painter, sort_indicator_rect, direction, priority);
+                                }
+
+                                if let (Some(filter_button_rect), Some(filter_kind)) = (filter_button_rect, column_filter_kind) {
+                                    let filter_button_id = ui.id().with("filter_column").with(mapped_column_index);
+                                    let filter_button_response = ui.interact(filter_button_rect.intersect(cell_clip_rect), filter_button_id, Sense::click());
+
+                                    let filter = temp_state.filters.get(&mapped_column_index);
+
+                                    let filter_button_painter = ui.painter()
+                                        .with_clip_rect(filter_button_rect.intersect(cell_clip_rect));
+                                    Self::paint_filter_button(ui, &filter_button_painter, filter_button_rect, filter.is_some(), filter_button_response.hovered());
+
+                                    let changed_filter = Popup::from_toggle_button_response(&filter_button_response)
+                                        .close_behavior(PopupCloseBehavior::CloseOnClickOutside)
+                                        .show(|ui| Self::show_filter_popup(ui, filter_button_id, self.parameters.strings, filter_kind, filter))
+                                        .and_then(|popup_response| popup_response.inner);
+
+                                    if let Some(filter) = changed_filter {
+                                        match &filter {
+                                            Some(filter) => temp_state.filters.insert(mapped_column_index, filter.clone()),
+                                            None => temp_state.filters.remove(&mapped_column_index),
+                                        };
+                                        actions.push(Action::FilterChanged { column: mapped_column_index, filter, filters: temp_state.filters.clone() });
+                                    }
+                                }
+
                                 if response.clicked() {
                                     match cell_kind {
+                                        CellKind::ColumnHeader if column_sortable && (!self.parameters.selectable_columns || sort_indicator_rect
+                                            .zip(response.interact_pointer_pos())
+                                            .is_some_and(|(rect, pos)| rect.contains(pos))) => {
+                                            let extend = ui.input(|i| i.modifiers.shift);
+                                            let direction = temp_state.sort.cycle(mapped_column_index, extend);
+
+                                            actions.push(Action::SortChanged { column: mapped_column_index, direction, spec: temp_state.sort.clone() });
+                                        }
                                         CellKind::RowHeader => {
                                             if self.parameters.selectable_rows {
-                                                match row_was_selected {
-                                                    true => { temp_state.row_selections.remove(&mapped_row_index); },
-                                                    false => { temp_state.row_selections.insert(mapped_row_index); },
-                                                }
+                                                temp_state.row_selection_anchor = apply_axis_selection(
+                                                    &mut temp_state.row_selections,
+                                                    temp_state.row_selection_anchor,
+                                                    mapped_row_index,
+                                                    modifiers,
+                                                    visible_indexes(dimensions.row_count, row_ordering, rows_to_filter),
+                                                );
                                                 request_row_selection_changed_action = true;
                                             }
                                         }
-                                        // TODO selectable columns?
+                                        CellKind::Corner => {
+                                            selection_action = Some(SelectionAction::Toggle);
+                                        }
+                                        CellKind::ColumnHeader if self.parameters.selectable_columns => {
+                                            let modifiers = ui.input(|i| i.modifiers);
+                                            temp_state.column_selection_anchor = apply_axis_selection(
+                                                &mut temp_state.column_selections,
+                                                temp_state.column_selection_anchor,
+                                                mapped_column_index,
+                                                modifiers,
+                                                visible_indexes(dimensions.column_count, column_ordering, columns_to_filter),
+                                            );
+                                            request_column_selection_changed_action = true;
+                                        }
                                         _ => {}
                                     }
                                 }
 
-                                if !matches!(cell_kind, CellKind::Corner) {
-                                    if let Some(label) = label {
-                                        if response.dragged() {
-                                            Tooltip::always_open(ctx.clone(), ui_layer_id, "_egui_deferred_table_dnd_".into(), PopupAnchor::Pointer)
-                                                .gap(12.0)
-                                                .show(|ui| {
-                                                    ui.horizontal(|ui| {
-                                                        ui.label(label);
-                                                    });
-                                                });
+                                let header = match cell_kind {
+                                    CellKind::ColumnHeader => Some((Axis::Column, mapped_column_index)),
+                                    CellKind::RowHeader => Some((Axis::Row, mapped_row_index)),
+                                    _ => None,
+                                };
+                                if let Some((axis, index)) = header {
+                                    // the popup id must not depend on the order the headers are shown in
+                                    let popup = Popup::context_menu(&response)
+                                        .id(ui.id().with("header_context_menu").with((axis, index)));
+
+                                    if axis == Axis::Column && self.parameters.column_header_menu {
+                                        let (item, chooser_actions) = popup
+                                            .show(|ui| {
+                                                // the renderer's items are added in a closable scope, so that calling `ui.close()`, as the
+                                                // default implementation does, only indicates there are no items.
+                                                let no_items = ui.scope_builder(UiBuilder::new().closable(), |ui| {
+                                                    renderer.header_context_menu(ui, axis, index);
+                                                    ui.should_close()
+                                                }).inner;
+                                                if !no_items {
+                                                    ui.separator();
+                                                }
+
+                                                let menu = ColumnHeaderMenu {
+                                                    sortable: column_sortable,
+                                                    hideable: visible_indexes(dimensions.column_count, column_ordering, columns_to_filter).count() > 1,
+                                                    resizable: header_column_parameters.is_some_and(|it| it.resizable),
+                                                    pin: header_column_parameters.and_then(|it| it.pinned),
+                                                };
+                                                let item = menu.show(ui, self.parameters.strings);
+
+                                                let chooser_actions = if self.parameters.column_chooser {
+                                                    ui.separator();
+                                                    // the sub-menu stays open while columns are hidden, shown and moved
+                                                    SubMenuButton::new(self.parameters.strings.columns())
+                                                        .config(MenuConfig::new().close_behavior(PopupCloseBehavior::CloseOnClickOutside))
+                                                        .ui(ui, |ui| self.build_column_chooser(dimensions.column_count, column_ordering, columns_to_filter).show(ui))
+                                                        .1
+                                                        .map(|sub_menu_response| sub_menu_response.inner)
+                                                        .unwrap_or_default()
+                                                } else {
+                                                    vec![]
+                                                };
+
+                                                (item, chooser_actions)
+                                            })
+                                            .map(|popup_response| popup_response.inner)
+                                            .unwrap_or_default();
+                                        actions.extend(chooser_actions);
+
+                                        match item {
+                                            Some(ColumnHeaderMenuItem::Sort(direction)) => {
+                                                temp_state.sort = SortSpec::from(vec![(index, direction)]);
+                                                actions.push(Action::SortChanged { column: index, direction: Some(direction), spec: temp_state.sort.clone() });
+                                            }
+                                            Some(ColumnHeaderMenuItem::Hide) => {
+                                                actions.push(Action::ColumnVisibilityChanged { column: index, visible: false, hidden: hidden_columns(columns_to_filter, index, false) });
+                                            }
+                                            Some(item @ (ColumnHeaderMenuItem::AutoFit | ColumnHeaderMenuItem::ResetWidth)) => {
+                                                let width = match item {
+                                                    ColumnHeaderMenuItem::AutoFit => temp_state.content_widths.get(&index).map(|width| {
+                                                        header_column_parameters
+                                                            .map_or(*width, |it| it.dimension_range.clamp(*width))
+                                                            .at_least(minimum_resize_size)
+                                                    }),
+                                                    _ => Some(Self::default_dimension(self.parameters.column_parameters, index, inner_cell_size.x)),
+                                                };
+                                                if let Some(width) = width {
+                                                    // change at the end of the frame to avoid cells being the old size.
+                                                    drag_action = Some(DragAction::SetWidth(index, width));
+                                                    actions.push(Action::ColumnWidthChanged { column: index, width });
+                                                }
+                                            }
+                                            Some(ColumnHeaderMenuItem::Pin(pin)) => {
+                                                actions.push(Action::ColumnPinChanged { column: index, pin });
+                                            }
+                                            None => {}
+                                        }
+                                    } else {
+                                        popup.show(|ui| renderer.header_context_menu(ui, axis, index));
+                                    }
+
+                                    if response.double_clicked() {
+                                        actions.push(Action::HeaderDoubleClicked { axis, index });
+                                    }
+                                    if response.secondary_clicked() {
+                                        actions.push(Action::HeaderSecondaryClicked { axis, index, pos: pointer_interact_pos });
+                                    }
+                                    if response.middle_clicked() {
+                                        actions.push(Action::HeaderMiddleClicked { axis, index });
+                                    }
+                                }
+
+                                if matches!(cell_kind, CellKind::Corner) && self.parameters.column_chooser {
+                                    let chooser_actions = Popup::context_menu(&response)
+                                        .id(ui.id().with("column_chooser"))
+                                        .close_behavior(PopupCloseBehavior::CloseOnClickOutside)
+                                        .show(|ui| self.build_column_chooser(dimensions.column_count, column_ordering, columns_to_filter).show(ui))
+                                        .map(|popup_response| popup_response.inner)
+                                        .unwrap_or_default();
+                                    actions.extend(chooser_actions);
+                                }
+
+                                if !matches!(cell_kind, CellKind::Corner) {
+                                    if let Some(label) = label {
+                                        if response.dragged() {
+                                            Tooltip::always_open(ctx.clone(), ui_layer_id, "_egui_deferred_table_dnd_".into(), PopupAnchor::Pointer)
+                                                .gap(12.0)
+                                                .show(|ui| {
+                                                    ui.horizontal(|ui| {
+                                                        ui.label(label);
+                                                    });
+                                                });
                                         }
                                     }
 
                                     // Highlight drop target
-                                    if response.dnd_hover_payload::<CellId>().is_some() {
+                                    if response.dnd_hover_payload::<CellId>().is_some_and(|payload| can_drop(*payload, cell_id)) {
                                         ui.painter().rect_filled(
                                             cell_clip_rect,
                                             CornerRadius::ZERO,
@@ -970,7 +2296,9 @@ impl<'a, DataSource> DeferredTable<'a, DataSource> {
                                     }
 
                                     // handle dnd release
-                                    if let Some(payload) = response.dnd_release_payload::<CellId>() {
+                                    if let Some(payload) = response.dnd_release_payload::<CellId>()
+                                        && can_drop(*payload, cell_id)
+                                    {
                                         match (*payload, cell_id) {
                                             // currently only dragging like onto like is supported.
                                             (CellId::MappedColumn(payload_index), CellId::MappedColumn(current_index)) => if payload_index != current_index {
@@ -981,6 +2309,10 @@ impl<'a, DataSource> DeferredTable<'a, DataSource> {
                                                 info!("dnd release: row {} -> row {}", payload_index, current_index);
                                                 actions.push(Action::RowReorder{ from: payload_index, to: current_index })
                                             }
+                                            (CellId::ColumnGroup(group), CellId::MappedColumn(current_index)) => {
+                                                info!("dnd release: column group {} -> column {}", group, current_index);
+                                                actions.push(Action::ColumnGroupReorder{ group, to: current_index })
+                                            }
                                             _ => ()
                                         }
                                     }
@@ -994,11 +2326,112 @@ impl<'a, DataSource> DeferredTable<'a, DataSource> {
                                 }
                             }
                             accumulated_row_heights += outer_row_height + 1.0;
+                            if matches!(row_kind, RowKind::HeaderRow) {
+                                accumulated_row_heights += column_groups_height;
+                            }
+                        }
+
+                        if column_group_levels > 0 {
+                            trace!("column groups");
+                            // the pinned-end column headers are shown last, the groups need them in visible order.
+                            shown_column_headers.sort_by_key(|(visible_column_index, ..)| *visible_column_index);
+                            let columns = shown_column_headers
+                                .iter()
+                                .map(|(_, mapped_column_index, ..)| (*mapped_column_index, column_pinning.pin(*mapped_column_index)))
+                                .collect::<Vec<_>>();
+
+                            for level in 0..column_group_levels {
+                                // the highest level is at the top
+                                let y = table_max_rect.min.y + (column_group_levels - 1 - level) as f32 * (outer_cell_size.y + 1.0);
+
+                                for (group_index, span) in column_groups::group_spans(self.parameters.column_groups, level, &columns) {
+                                    let group = &self.parameters.column_groups[group_index];
+                                    let (_, _, first_rect, first_clip_rect) = shown_column_headers[span.start];
+                                    let (_, _, last_rect, last_clip_rect) = shown_column_headers[span.end - 1];
+
+                                    let group_rect = Rect::from_x_y_ranges(first_rect.min.x..=last_rect.max.x, y..=y + outer_cell_size.y);
+                                    let group_clip_rect = Rect::from_x_y_ranges(first_clip_rect.min.x..=last_clip_rect.max.x, group_rect.y_range())
+                                        .intersect(parent_clip_rect);
+
+                                    ui.painter()
+                                        .with_clip_rect(group_clip_rect)
+                                        .rect_filled(group_rect, 0.0, header_row_bg_color);
+
+                                    let cell_id = CellId::ColumnGroup(group_index);
+                                    let response = ui.allocate_rect(group_clip_rect, Sense::drag());
+                                    response.dnd_set_drag_payload(cell_id);
+
+                                    // the label starts at the visible part of the group, so it stays visible when scrolling.
+                                    let label_rect = group_rect.shrink2(outer_inner_half_difference).intersect(group_clip_rect);
+                                    let mut cell_ui = ui.new_child(UiBuilder::new()
+                                        .id_salt((cell_id, span.start))
+                                        .max_rect(label_rect));
+                                    cell_ui.set_clip_rect(label_rect);
+                                    cell_ui.style_mut().wrap_mode = Some(egui::TextWrapMode::Extend);
+                                    cell_ui.add(egui::Label::new(&group.label).selectable(false));
+
+                                    if response.dragged() {
+                                        Tooltip::always_open(ctx.clone(), ui_layer_id, "_egui_deferred_table_dnd_".into(), PopupAnchor::Pointer)
+                                            .gap(12.0)
+                                            .show(|ui| {
+                                                ui.horizontal(|ui| {
+                                                    ui.label(&group.label);
+                                                });
+                                            });
+                                    }
+                                }
+                            }
                         }
 
                         trace!("cells");
 
-                        let cells_clip_rect = Rect::from_min_max((table_max_rect.min + outer_cell_size) + Vec2::splat(1.0), translated_viewport_rect.max).intersect(parent_clip_rect);
+                        let visible_cell_selection = VisibleCellSelection::new(&temp_state.cell_selections, dimensions, row_ordering, column_ordering);
+
+                        // the spans covering the shown cells, including spans whose origin is not shown.
+                        let cell_spans = match data_source.has_cell_spans() {
+                            true => {
+                                let shown_rows = row_slots
+                                    .iter()
+                                    .skip(1)
+                                    .filter_map(|slot| match layout_row(slot.visible_index) {
+                                        (_, visible_row_index, LayoutRow::Row(_)) => Some(visible_row_index),
+                                        (_, _, LayoutRow::GroupHeader(_)) => None,
+                                    })
+                                    .collect::<Vec<_>>();
+                                let shown_columns = column_slots.iter().skip(1).map(|slot| slot.visible_index).collect::<Vec<_>>();
+                                // spans end at the edges of the pinned bands and at the group header rows.
+                                let scrolled_rows = row_pinning.scrolled_range(dimensions.row_count);
+                                let scrolled_columns = column_pinning.scrolled_range(dimensions.column_count);
+                                let group_starts = row_layout.as_ref().map(|row_layout| row_layout.group_starts.as_slice()).unwrap_or_default();
+                                CellSpans::new(
+                                    &SpanAxis::new(dimensions.row_count, row_ordering, rows_to_filter, [scrolled_rows.start, scrolled_rows.end].into_iter().chain(group_starts.iter().copied()), &shown_rows),
+                                    &SpanAxis::new(dimensions.column_count, column_ordering, columns_to_filter, [scrolled_columns.start, scrolled_columns.end], &shown_columns),
+                                    |cell_index| data_source.cell_span(cell_index),
+                                )
+                            }
+                            false => CellSpans::default(),
+                        };
+                        // the origins of the spans that have been shown, each span is shown at the first of its cells that is shown.
+                        let mut shown_spans = BTreeSet::new();
+
+                        // the first visible column shows the tree, see `DeferredTableDataSource::tree_row`.
+                        let tree_column = visible_indexes(dimensions.column_count, column_ordering, columns_to_filter).next();
+                        let tree_indent = ui.spacing().indent;
+
+                        // pinned columns are not expanded
+                        let column_parameters = self.parameters.column_parameters;
+                        let column_width = |mapped_column_index: usize, pinned: bool| {
+                            let mut width = state.column_widths[mapped_column_index];
+                            if let Some(column_parameters) = column_parameters {
+                                match column_parameters.get(mapped_column_index) {
+                                    Some(params) if params.expandable && !pinned => {
+                                        width += additional_width
+                                    }
+                                    _ => {}
+                                }
+                            }
+                            width
+                        };
                         if false {
                             ui.painter().debug_rect(cells_clip_rect, Color32::CYAN, "cr");
                         }
@@ -1018,21 +2451,23 @@ impl<'a, DataSource> DeferredTable<'a, DataSource> {
                             row_counter = cell_origin.row + 1 - first_row_filtered_count;
 
                             // start with an offset equal to header height, which is currently using the cell_size
-                            let mut accumulated_row_heights = outer_cell_size.y + 1.0;
-                            for grid_row_index in 1..=visible_row_count {
-                                if grid_row_index + cell_origin.row > dimensions.row_count {
-                                    break
-                                }
-
-                                let visible_row_index = cell_origin.row + (grid_row_index.saturating_sub(1));
-                                let mapped_row_index = Self::map_index(dimensions.row_count, row_ordering, visible_row_index);
+                            let mut accumulated_row_heights = header_size.y + 1.0;
+                            for row_slot in row_slots.iter().skip(1) {
+                                let grid_row_index = row_slot.grid_index;
+                                let (layout_index, visible_row_index, layout_row) = layout_row(row_slot.visible_index);
 
-                                if let Some(rows_to_filter) = &rows_to_filter {
-                                    if rows_to_filter.contains(&mapped_row_index) {
+                                if let Some(rows_to_filter) = &layout_rows_to_filter {
+                                    if rows_to_filter.contains(&layout_index) {
                                         trace!("filtered row");
                                         continue;
                                     }
                                 }
+                                let LayoutRow::Row(mapped_row_index) = layout_row else {
+                                    // the group header rows are shown after the cells.
+                                    accumulated_row_heights += outer_cell_size.y + 1.0;
+                                    row_counter += 1;
+                                    continue;
+                                };
                                 row_counter += 1;
 
                                 let inner_row_height = state.row_heights[mapped_row_index];
@@ -1046,18 +2481,15 @@ impl<'a, DataSource> DeferredTable<'a, DataSource> {
 
                                 let row_bg_color = Self::pick_row_bg_color(opaque_faint_bg_color, opaque_faint_selected_bg_color, ui, row_counter, row_was_selected);
 
-                                let y = start_pos.y + accumulated_row_heights;
+                                let y = row_slot.position.unwrap_or(start_pos.y + accumulated_row_heights);
 
                                 // start with an offset equal to header width, which is currently using the cell_size
                                 let mut accumulated_column_widths = outer_cell_size.x + 1.0;
 
-                                for grid_column_index in 1..=visible_column_count {
-                                    if grid_column_index + cell_origin.column > dimensions.column_count {
-                                        break
-                                    }
-
-                                    let visible_column_index = cell_origin.column + (grid_column_index - 1);
-                                    let mapped_column_index = Self::map_index(dimensions.column_count, column_ordering, visible_column_index);
+                                for column_slot in column_slots.iter().skip(1) {
+                                    let grid_column_index = column_slot.grid_index;
+                                    let visible_column_index = column_slot.visible_index;
+                                    let mapped_column_index = map_index(dimensions.column_count, column_ordering, visible_column_index);
 
                                     if let Some(columns_to_filter) = &columns_to_filter {
                                         if columns_to_filter.contains(&mapped_column_index) {
@@ -1066,30 +2498,51 @@ impl<'a, DataSource> DeferredTable<'a, DataSource> {
                                         }
                                     }
 
-                                    let inner_column_width = {
-                                        let mut width = state.column_widths[mapped_column_index];
-                                        if let Some(column_parameters) = self.parameters.column_parameters {
-                                            match column_parameters.get(mapped_column_index) {
-                                                Some(params) if params.expandable => {
-                                                    width += additional_width
-                                                }
-                                                _ => {}
-                                            }
-                                        }
-                                        width
-                                    };
+                                    let inner_column_width = column_width(mapped_column_index, column_slot.position.is_some());
                                     let outer_column_width = inner_column_width + outer_inner_difference.x;
 
-                                    let cell_index = CellIndex {
-                                        row: mapped_row_index,
-                                        column: mapped_column_index,
-                                    };
-
-                                    let x = start_pos.x + accumulated_column_widths;
+                                    let x = column_slot.position.unwrap_or(start_pos.x + accumulated_column_widths);
                                     accumulated_column_widths += outer_column_width + 1.0;
 
-                                    let cell_rect = Rect::from_min_size(Pos2::new(x, y), (outer_column_width, outer_row_height).into());
-                                    let cell_clip_rect = cell_rect.intersect(cells_clip_rect);
+                                    let cell_span = cell_spans.find(visible_row_index, visible_column_index);
+                                    if cell_span.is_some_and(|span| !shown_spans.insert(span.origin)) {
+                                        trace!("spanned cell");
+                                        continue;
+                                    }
+
+                                    // a span is shown as a single cell, using the origin of the span, across all of its rows/columns.
+                                    let (cell_index, cell_rect, visible_row_index, visible_column_index, mapped_column_index) = match cell_span {
+                                        Some(span) => {
+                                            let column_size = |visible_column_index: usize| {
+                                                let mapped_column_index = map_index(dimensions.column_count, column_ordering, visible_column_index);
+                                                (!columns_to_filter.is_some_and(|columns| columns.contains(&mapped_column_index)))
+                                                    .then(|| column_width(mapped_column_index, column_slot.position.is_some()) + outer_inner_difference.x + 1.0)
+                                            };
+                                            let row_size = Self::size_of_index(&state.row_heights, rows_to_filter, outer_inner_difference.y + 1.0);
+                                            let row_size = |visible_row_index: usize| row_size(map_index(dimensions.row_count, row_ordering, visible_row_index));
+
+                                            let min = Pos2::new(
+                                                x - (span.columns.start..visible_column_index).filter_map(column_size).sum::<f32>(),
+                                                y - (span.rows.start..visible_row_index).filter_map(row_size).sum::<f32>(),
+                                            );
+                                            let size = Vec2::new(
+                                                span.columns.clone().filter_map(column_size).sum::<f32>(),
+                                                span.rows.clone().filter_map(row_size).sum::<f32>(),
+                                            ) - Vec2::splat(1.0);
+
+                                            (span.origin, Rect::from_min_size(min, size), span.rows.start, span.columns.start, span.origin.column)
+                                        }
+                                        None => (
+                                            CellIndex { row: mapped_row_index, column: mapped_column_index },
+                                            Rect::from_min_size(Pos2::new(x, y), (outer_column_width, outer_row_height).into()),
+                                            visible_row_index,
+                                            visible_column_index,
+                                            mapped_column_index,
+                                        ),
+                                    };
+                                    let cell_clip_rect = cell_rect
+                                        .intersect(cells_clip_rect)
+                                        .intersect(Rect::from_x_y_ranges(column_slot.clip, row_slot.clip));
                                     let cell_clip_rect_size = cell_clip_rect.size();
 
                                     let cell_inner_rect = cell_rect.shrink2(outer_inner_half_difference);
@@ -1104,8 +2557,36 @@ impl<'a, DataSource> DeferredTable<'a, DataSource> {
                                     }
 
                                     let response = ui.allocate_rect(cell_clip_rect, Sense::click());
+                                    // the sticky group header is shown above the cells
+                                    let contains_pointer = response.contains_pointer()
+                                        && !sticky_group_header.is_some_and(|(_, header_rect): (usize, Rect)| header_rect.contains(pointer_interact_pos));
+
+                                    // the contents are indented by the depth of the row, plus the space for the disclosure triangle.
+                                    let tree_row = if Some(mapped_column_index) == tree_column {
+                                        data_source.tree_row(cell_index.row)
+                                    } else {
+                                        None
+                                    };
+                                    let content_indent = tree_row.map_or(0.0, |tree_row| (tree_row.depth + 1) as f32 * tree_indent);
+                                    let disclosure_rect = tree_row.filter(|tree_row| tree_row.has_children).map(|tree_row| {
+                                        Rect::from_center_size(
+                                            Pos2::new(cell_inner_rect.min.x + (tree_row.depth as f32 + 0.5) * tree_indent, cell_inner_rect.center().y),
+                                            Vec2::splat(tree_indent),
+                                        )
+                                    });
 
-                                    let bg_color = if self.parameters.highlight_hovered_cell && response.contains_pointer() {
+                                    let bg_color = if search_matches.contains(&cell_index) {
+                                        let highlight_color = ui.style().visuals.warn_fg_color;
+                                        if temp_state.search.current == Some(cell_index) {
+                                            highlight_color.gamma_multiply(0.6)
+                                        } else {
+                                            highlight_color.gamma_multiply(0.25)
+                                        }
+                                    } else if self.parameters.selectable_cells && visible_cell_selection.contains(visible_row_index, visible_column_index) {
+                                        ui.style().visuals.selection.bg_fill.gamma_multiply(0.5)
+                                    } else if self.parameters.selectable_columns && temp_state.column_selections.contains(&mapped_column_index) {
+                                        Self::pick_row_bg_color(opaque_faint_bg_color, opaque_faint_selected_bg_color, ui, row_counter, true)
+                                    } else if self.parameters.highlight_hovered_cell && contains_pointer {
                                         ui.style().visuals.widgets.hovered.weak_bg_fill
                                     } else {
                                         row_bg_color
@@ -1115,20 +2596,87 @@ impl<'a, DataSource> DeferredTable<'a, DataSource> {
                                         .with_clip_rect(cell_clip_rect)
                                         .rect_filled(cell_rect, 0.0, bg_color);
 
-                                    // note: cannot use 'response.clicked()' here as the the cell 'swallows' the click if the contents are interactive.
-                                    if response.contains_pointer() && ui.ctx().input(|i| i.pointer.primary_released()) {
-                                        // FIXME this doesn't track if the click location is in the same cell, that is, this will
-                                        //       be triggered if you click somewhere, then release in this cell.
-                                        //       which is not the intention.
+                                    if self.parameters.selectable_cells {
+                                        if primary_pressed && contains_pointer {
+                                            match temp_state.cell_selections.last_mut() {
+                                                Some(range) if modifiers.shift => range.end = cell_index,
+                                                _ => {
+                                                    if !modifiers.command {
+                                                        temp_state.cell_selections.clear();
+                                                    }
+                                                    temp_state.cell_selections.push(CellRange::single(cell_index));
+                                                }
+                                            }
+                                            temp_state.selecting_cells = true;
+                                            request_cell_selection_changed_action = true;
+                                        } else if temp_state.selecting_cells
+                                            && cell_clip_rect.contains(selection_pointer_pos)
+                                            && let Some(range) = temp_state.cell_selections.last_mut()
+                                            && range.end != cell_index
+                                        {
+                                            range.end = cell_index;
+                                            request_cell_selection_changed_action = true;
+                                        }
+                                    }
 
-                                        actions.push(Action::CellClicked(cell_index));
+                                    // note: cannot use 'response.clicked()' here as the the cell 'swallows' the click if the contents are interactive.
+                                    let click = if contains_pointer {
+                                        ctx.input(|i| CellPress::update(&mut temp_state.cell_press, cell_index, pointer_interact_pos, i, &input_options))
+                                    } else {
+                                        None
+                                    };
 
-                                        if let (Some(editor), Some(edit_state)) = (editor.as_mut(), edit_state.as_mut()) {
-                                            self.handle_editable_cell_click(data_source, cell_index, *editor, *edit_state);
+                                    match click {
+                                        Some(CellClick::Primary { .. }) if disclosure_rect.is_some_and(|rect| rect.contains(pointer_interact_pos)) => {
+                                            let expanded = temp_state.collapsed_rows.contains(&cell_index.row);
+                                            if expanded {
+                                                temp_state.collapsed_rows.remove(&cell_index.row);
+                                            } else {
+                                                temp_state.collapsed_rows.insert(cell_index.row);
+                                            }
+                                            actions.push(Action::RowExpansionChanged {
+                                                row: cell_index.row,
+                                                expanded,
+                                                collapsed: temp_state.collapsed_rows.clone(),
+                                            });
+                                            // the rows are filtered before the cells are shown.
+                                            ctx.request_repaint();
                                         }
+                                        Some(CellClick::Primary { double }) => {
+                                            actions.push(Action::CellClicked(cell_index));
+                                            if double {
+                                                actions.push(Action::CellDoubleClicked(cell_index));
+                                            }
+                                            temp_state.active_cell = Some(cell_index);
+
+                                            if let (Some(editor), Some(edit_state)) = (editor.as_mut(), edit_state.as_mut()) {
+                                                if self.parameters.edit_on_double_click && !matches!(edit_state.state, Some(CellEditState::Editing(..))) {
+                                                    // clicking only changes the pivot
+                                                    edit_state.state = Some(CellEditState::Pivot(cell_index));
+                                                    if double {
+                                                        Self::begin_edit(data_source, cell_index, *editor, edit_state);
+                                                    }
+                                                } else {
+                                                    self.handle_editable_cell_click(data_source, cell_index, *editor, *edit_state);
+                                                }
+                                            }
+                                        }
+                                        Some(CellClick::Secondary(pos)) => actions.push(Action::CellSecondaryClicked { cell_index, pos }),
+                                        Some(CellClick::Middle) => actions.push(Action::CellMiddleClicked(cell_index)),
+                                        None => {}
+                                    }
+
+                                    if let Some(disclosure_rect) = disclosure_rect {
+                                        let expanded = !temp_state.collapsed_rows.contains(&cell_index.row);
+                                        let hovered = contains_pointer && disclosure_rect.contains(pointer_interact_pos);
+                                        Self::paint_disclosure_triangle(ui, &ui.painter().with_clip_rect(cell_inner_clip_rect), disclosure_rect, expanded, hovered);
                                     }
 
-                                    // TODO track double clicks
+                                    if temp_state.active_cell == Some(cell_index) {
+                                        ui.painter()
+                                            .with_clip_rect(cell_clip_rect)
+                                            .rect_stroke(cell_rect, CornerRadius::ZERO, ui.style().visuals.selection.stroke, StrokeKind::Inside);
+                                    }
 
                                     if SHOW_CELL_BORDERS {
                                         ui.painter()
@@ -1141,7 +2689,7 @@ impl<'a, DataSource> DeferredTable<'a, DataSource> {
                                     let mut cell_ui = ui.new_child(UiBuilder::new()
                                         .id_salt(cell_id)
                                         .ui_stack_info(UiStackInfo::new(UiKind::TableCell))
-                                        .max_rect(cell_inner_rect));
+                                        .max_rect(cell_inner_rect.with_min_x((cell_inner_rect.min.x + content_indent).min(cell_inner_rect.max.x))));
                                     cell_ui.set_clip_rect(cell_inner_clip_rect);
                                     cell_ui.style_mut().wrap_mode = Some(egui::TextWrapMode::Extend);
 
@@ -1214,21 +2762,106 @@ impl<'a, DataSource> DeferredTable<'a, DataSource> {
 
                                     if !handled {
                                         renderer.render_cell(&mut cell_ui, cell_index, data_source);
+                                        // spans are wider than the column
+                                        if cell_span.is_none() {
+                                            Self::measure_content_width(&mut content_widths, mapped_column_index, cell_ui.min_rect().width() + content_indent);
+                                        }
                                     }
+
+                                    // note: cannot use 'response.context_menu()' here, for the same reason as above.
+                                    Popup::context_menu(&response)
+                                        .id(ui.id().with("cell_context_menu").with(cell_index))
+                                        .open_memory(match click {
+                                            Some(CellClick::Secondary(_)) => Some(SetOpenCommand::Bool(true)),
+                                            Some(CellClick::Primary { .. }) => Some(SetOpenCommand::Bool(false)),
+                                            _ => None,
+                                        })
+                                        .show(|ui| renderer.cell_context_menu(ui, cell_index, data_source));
                                 }
                                 accumulated_row_heights += outer_row_height + 1.0;
                             }
                         });
 
+                        if let Some(row_groups) = &row_groups {
+                            trace!("row groups");
+                            for (group_index, header_rect) in shown_group_headers.iter().copied().chain(sticky_group_header) {
+                                let header_clip_rect = Rect::from_x_y_ranges(header_rect.x_range(), scrolled_rows_clip)
+                                    .intersect(header_rect)
+                                    .intersect(parent_clip_rect);
+                                if !header_clip_rect.is_positive() {
+                                    continue;
+                                }
+
+                                let group = &row_groups.groups[group_index];
+                                let expanded = !temp_state.collapsed_groups.contains(&group.key);
+                                let aggregates = self.group_aggregates.as_ref().map(|aggregates| aggregates(data_source, &group.rows));
+
+                                let response = ui.allocate_rect(header_clip_rect, Sense::click());
+                                Self::paint_group_header(
+                                    ui,
+                                    &ui.painter().with_clip_rect(header_clip_rect),
+                                    header_rect,
+                                    &self.parameters.strings.group_label(&group.key, group.rows.len()),
+                                    aggregates.as_deref(),
+                                    expanded,
+                                    response.hovered(),
+                                    header_row_bg_color,
+                                );
+
+                                if response.clicked() {
+                                    if expanded {
+                                        temp_state.collapsed_groups.insert(group.key.clone());
+                                    } else {
+                                        temp_state.collapsed_groups.remove(&group.key);
+                                    }
+                                    actions.push(Action::RowGroupExpansionChanged {
+                                        group: group.key.clone(),
+                                        expanded: !expanded,
+                                        collapsed: temp_state.collapsed_groups.clone(),
+                                    });
+                                    // the rows are filtered before the cells are shown.
+                                    ctx.request_repaint();
+                                }
+                            }
+                        }
+
+                        // auto-scroll when dragging a selection outside the cells
+                        if temp_state.selecting_cells || temp_state.selecting_rows.is_some() {
+                            let mut scroll_delta = selection_pointer_pos - pointer_interact_pos;
+                            if !temp_state.selecting_cells {
+                                // only scroll vertically when selecting rows
+                                scroll_delta.x = 0.0;
+                            }
+                            if scroll_delta != Vec2::ZERO {
+                                ui.scroll_with_delta(scroll_delta);
+                                ui.ctx().request_repaint();
+                            }
+                        }
+
                         let line_stroke = ui.style().visuals.window_stroke;
                         ui.painter()
                             .with_clip_rect(inner_max_rect)
-                            .hline(table_max_rect.min.x..=table_max_rect.min.x + table_width, table_max_rect.min.y + outer_cell_size.y, line_stroke);
+                            .hline(table_max_rect.min.x..=table_max_rect.min.x + table_width, table_max_rect.min.y + header_size.y, line_stroke);
 
                         ui.painter()
                             .with_clip_rect(inner_max_rect)
                             .vline(table_max_rect.min.x + outer_cell_size.x, table_max_rect.min.y..=table_max_rect.min.y + table_height, line_stroke);
 
+                        // separate the pinned bands from the scrolled cells
+                        let separator_painter = ui.painter().with_clip_rect(inner_max_rect);
+                        for x in [
+                            (pinned_start_width > 0.0).then_some(pinned_start_x + pinned_start_width - 1.0),
+                            (pinned_end_width > 0.0).then_some(pinned_end_x - 1.0),
+                        ].into_iter().flatten() {
+                            separator_painter.vline(x, table_max_rect.min.y..=table_max_rect.min.y + table_height, line_stroke);
+                        }
+                        for y in [
+                            (pinned_start_height > 0.0).then_some(pinned_start_y + pinned_start_height - 1.0),
+                            (pinned_end_height > 0.0).then_some(pinned_end_y - 1.0),
+                        ].into_iter().flatten() {
+                            separator_painter.hline(table_max_rect.min.x..=table_max_rect.min.x + table_width, y, line_stroke);
+                        }
+
                         ui.response()
                     });
             });
@@ -1238,12 +2871,137 @@ impl<'a, DataSource> DeferredTable<'a, DataSource> {
             temp_state.drag_state = None;
         }
 
+        if let Some(selection_action) = selection_action {
+            let visible_rows = visible_indexes(dimensions.row_count, row_ordering, rows_to_filter)
+                .collect::<BTreeSet<_>>();
+            // the range from the first visible cell to the last visible cell, if there are any visible cells
+            let all_cells_range = {
+                let mut visible_columns =
+                    visible_indexes(dimensions.column_count, column_ordering, columns_to_filter);
+                let mut visible_rows =
+                    visible_indexes(dimensions.row_count, row_ordering, rows_to_filter);
+                let first = visible_rows.next().zip(visible_columns.next());
+                first.map(|(first_row, first_column)| {
+                    let (last_row, last_column) = (
+                        visible_rows.last().unwrap_or(first_row),
+                        visible_columns.last().unwrap_or(first_column),
+                    );
+                    CellRange::new(
+                        CellIndex {
+                            row: first_row,
+                            column: first_column,
+                        },
+                        CellIndex {
+                            row: last_row,
+                            column: last_column,
+                        },
+                    )
+                })
+            };
+            let all_cells_selection = all_cells_range.into_iter().collect::<Vec<_>>();
+
+            let all_selected = (!self.parameters.selectable_rows
+                || temp_state.row_selections == visible_rows)
+                && (!self.parameters.selectable_cells
+                    || temp_state.cell_selections == all_cells_selection);
+
+            let select_all = match selection_action {
+                SelectionAction::Toggle => !all_selected,
+                SelectionAction::SelectAll => true,
+                SelectionAction::Clear => false,
+            };
+
+            let (row_selections, cell_selections) = if select_all {
+                (visible_rows, all_cells_selection)
+            } else {
+                (BTreeSet::new(), vec![])
+            };
+
+            if self.parameters.selectable_rows && temp_state.row_selections != row_selections {
+                temp_state.row_selections = row_selections;
+                temp_state.row_selection_anchor = None;
+                request_row_selection_changed_action = true;
+            }
+            if self.parameters.selectable_cells && temp_state.cell_selections != cell_selections {
+                temp_state.cell_selections = cell_selections;
+                request_cell_selection_changed_action = true;
+            }
+            if !select_all
+                && self.parameters.selectable_columns
+                && !temp_state.column_selections.is_empty()
+            {
+                temp_state.column_selections.clear();
+                temp_state.column_selection_anchor = None;
+                request_column_selection_changed_action = true;
+            }
+
+            ctx.request_repaint();
+        }
+
+        if self.parameters.search_bar
+            && ui.rect_contains_pointer(outer_max_rect)
+            && ctx.input_mut(|i| i.consume_key(Modifiers::COMMAND, Key::F))
+        {
+            temp_state.search.request_focus = true;
+            ctx.request_repaint();
+        }
+
+        if request_cell_selection_changed_action {
+            actions.push(Action::CellSelectionChanged {
+                selection: temp_state.cell_selections.clone(),
+            });
+            // the selection is painted before the cells are interacted with.
+            ctx.request_repaint();
+        }
+
+        if request_column_selection_changed_action {
+            actions.push(Action::ColumnSelectionChanged {
+                selection: temp_state.column_selections.clone(),
+            });
+        }
+
         if request_row_selection_changed_action {
             actions.push(Action::RowSelectionChanged {
                 selection: temp_state.row_selections.clone(),
             });
         }
 
+        if temp_state.active_cell != previous_active_cell
+            && let Some(cell_index) = temp_state.active_cell
+        {
+            actions.push(Action::ActiveCellChanged { cell_index });
+            // the previous active cell could have already been painted.
+            ctx.request_repaint();
+        }
+
+        temp_state.content_widths = content_widths;
+
+        if let Some(history) = edit_state
+            .as_mut()
+            .and_then(|edit_state| edit_state.history.as_mut())
+        {
+            for action in &actions {
+                let entry = match action {
+                    Action::ColumnReorder { .. } | Action::ColumnGroupReorder { .. }
+                        if history.records_reordering() =>
+                    {
+                        HistoryEntry::ColumnOrdering(
+                            renderer.column_ordering().map(<[usize]>::to_vec),
+                        )
+                    }
+                    Action::RowReorder { .. } if history.records_reordering() => {
+                        HistoryEntry::RowOrdering(renderer.row_ordering().map(<[usize]>::to_vec))
+                    }
+                    Action::ColumnWidthChanged { column, .. } => HistoryEntry::ColumnWidth {
+                        column: *column,
+                        width: state.column_widths[*column],
+                    },
+                    _ => continue,
+                };
+                history.record(entry);
+            }
+        }
+
         let repaint = match drag_action.take() {
             None => false,
             Some(DragAction::SetWidth(index, new_width)) => {
@@ -1260,12 +3018,54 @@ impl<'a, DataSource> DeferredTable<'a, DataSource> {
             ui.ctx().request_repaint();
         }
 
+        if let Some(history) = edit_state
+            .as_mut()
+            .and_then(|edit_state| edit_state.history.as_mut())
+        {
+            // only resizes that changed the width/height are recorded, the new width/height has been applied above.
+            for entry in resize_entries {
+                let changed = match &entry {
+                    HistoryEntry::ColumnWidth { column, width } => {
+                        state.column_widths[*column] != *width
+                    }
+                    HistoryEntry::RowHeight { row, height } => state.row_heights[*row] != *height,
+                    _ => true,
+                };
+                if changed {
+                    history.record(entry);
+                }
+            }
+        }
+
         DeferredTablePersistentState::store(ui.ctx(), persistent_state_id, state);
         DeferredTableTempState::store(ui.ctx(), temp_state_id, temp_state);
 
         (ui.response(), actions)
     }
 
+    fn build_column_chooser<'b>(
+        &'b self,
+        column_count: usize,
+        column_ordering: &'b [usize],
+        columns_to_filter: Option<&'b [usize]>,
+    ) -> ColumnChooser<'b> {
+        let mut column_chooser =
+            ColumnChooser::new(column_count, Some(column_ordering), columns_to_filter)
+                .strings(self.parameters.strings);
+        if let Some(column_parameters) = self.parameters.column_parameters {
+            column_chooser = column_chooser.column_parameters(column_parameters);
+        }
+        if self.parameters.zero_based_headers {
+            column_chooser = column_chooser.zero_based_headers();
+        }
+        column_chooser
+    }
+
+    fn measure_content_width(content_widths: &mut BTreeMap<usize, f32>, column: usize, width: f32) {
+        let content_width = content_widths.entry(column).or_default();
+        *content_width = content_width.max(width);
+    }
+
     fn pick_row_bg_color(
         opaque_faint_bg_color: Color32,
         opaque_faint_selected_bg_color: Color32,
@@ -1315,6 +3115,352 @@ impl<'a, DataSource> DeferredTable<'a, DataSource> {
         }
     }
 
+    fn paint_sort_indicator(
+        ui: &Ui,
+        painter: &Painter,
+        rect: Rect,
+        direction: SortDirection,
+        priority: Option<usize>,
+    ) {
+        let color = ui.style().visuals.strong_text_color();
+        let half_size = rect.height() * 0.2;
+
+        let mut center = rect.center();
+        if let Some(priority) = priority {
+            // arrow on the right, one-based priority number on the left
+            center.x = rect.max.x - half_size;
+
+            let font_id = egui::TextStyle::Small.resolve(ui.style());
+            painter.text(
+                Pos2::new(center.x - half_size, center.y),
+                egui::Align2::RIGHT_CENTER,
+                (priority + 1).to_string(),
+                font_id,
+                color,
+            );
+        }
+
+        let points = match direction {
+            SortDirection::Ascending => vec![
+                center + Vec2::new(0.0, -half_size),
+                center + Vec2::new(half_size, half_size),
+                center + Vec2::new(-half_size, half_size),
+            ],
+            SortDirection::Descending => vec![
+                center + Vec2::new(-half_size, -half_size),
+                center + Vec2::new(half_size, -half_size),
+                center + Vec2::new(0.0, half_size),
+            ],
+        };
+
+        painter.add(Shape::convex_polygon(points, color, Stroke::NONE));
+    }
+
+    fn paint_disclosure_triangle(
+        ui: &Ui,
+        painter: &Painter,
+        rect: Rect,
+        expanded: bool,
+        hovered: bool,
+    ) {
+        let color = if hovered {
+            ui.style().visuals.strong_text_color()
+        } else {
+            ui.style().visuals.text_color()
+        };
+        let half_size = rect.height() * 0.2;
+        let center = rect.center();
+
+        let points = if expanded {
+            vec![
+                center + Vec2::new(-half_size, -half_size * 0.5),
+                center + Vec2::new(half_size, -half_size * 0.5),
+                center + Vec2::new(0.0, half_size),
+            ]
+        } else {
+            vec![
+                center + Vec2::new(-half_size * 0.5, -half_size),
+                center + Vec2::new(half_size, 0.0),
+                center + Vec2::new(-half_size * 0.5, half_size),
+            ]
+        };
+
+        painter.add(Shape::convex_polygon(points, color, Stroke::NONE));
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn paint_group_header(
+        ui: &Ui,
+        painter: &Painter,
+        rect: Rect,
+        label: &str,
+        aggregates: Option<&str>,
+        expanded: bool,
+        hovered: bool,
+        bg_color: Color32,
+    ) {
+        painter.rect_filled(rect, 0.0, bg_color);
+
+        let indent = ui.spacing().indent;
+        let disclosure_rect = Rect::from_center_size(
+            Pos2::new(rect.min.x + indent / 2.0, rect.center().y),
+            Vec2::splat(indent.min(rect.height())),
+        );
+        Self::paint_disclosure_triangle(ui, painter, disclosure_rect, expanded, hovered);
+
+        let font_id = egui::TextStyle::Body.resolve(ui.style());
+        let label_rect = painter.text(
+            Pos2::new(rect.min.x + indent, rect.center().y),
+            egui::Align2::LEFT_CENTER,
+            label,
+            font_id.clone(),
+            ui.style().visuals.strong_text_color(),
+        );
+        if let Some(aggregates) = aggregates {
+            painter.text(
+                Pos2::new(label_rect.max.x + indent, rect.center().y),
+                egui::Align2::LEFT_CENTER,
+                aggregates,
+                font_id,
+                ui.style().visuals.weak_text_color(),
+            );
+        }
+    }
+
+    fn paint_filter_button(ui: &Ui, painter: &Painter, rect: Rect, active: bool, hovered: bool) {
+        let color = if active {
+            ui.style().visuals.selection.stroke.color
+        } else if hovered {
+            ui.style().visuals.strong_text_color()
+        } else {
+            ui.style().visuals.weak_text_color()
+        };
+        let half_size = rect.height() * 0.2;
+        let center = rect.center();
+
+        // a funnel
+        painter.add(Shape::convex_polygon(
+            vec![
+                center + Vec2::new(-half_size, -half_size),
+                center + Vec2::new(half_size, -half_size),
+                center + Vec2::new(half_size * 0.25, 0.0),
+                center + Vec2::new(-half_size * 0.25, 0.0),
+            ],
+            color,
+            Stroke::NONE,
+        ));
+        painter.rect_filled(
+            Rect::from_min_max(
+                center + Vec2::new(-half_size * 0.25, 0.0),
+                center + Vec2::new(half_size * 0.25, half_size),
+            ),
+            0.0,
+            color,
+        );
+    }
+
+    /// returns the matches for the query and the match to scroll to, if the user navigated to a match.
+    ///
+    /// `find` searches the cells for the query, it's only called when the query or the inputs have changed.
+    fn show_search_bar(
+        ui: &mut Ui,
+        strings: &dyn DeferredTableStrings,
+        search: &mut SearchState,
+        inputs: SearchInputs,
+        find: impl FnOnce(&str) -> SearchMatches,
+    ) -> (Arc<SearchMatches>, Option<CellIndex>) {
+        ui.horizontal(|ui| {
+            let response = ui.add(
+                TextEdit::singleline(&mut search.query)
+                    .hint_text(strings.search_hint())
+                    .desired_width(200.0),
+            );
+            if mem::take(&mut search.request_focus) {
+                response.request_focus();
+            }
+
+            // `None` for no navigation, otherwise `Some(backwards)`
+            let mut navigation = None;
+
+            // Note: single-line text edits lose focus when enter or escape is pressed
+            if response.lost_focus() {
+                let (enter, escape, shift) = ui.input(|i| {
+                    (
+                        i.key_pressed(Key::Enter),
+                        i.key_pressed(Key::Escape),
+                        i.modifiers.shift,
+                    )
+                });
+                if enter {
+                    navigation = Some(shift);
+                    // keep the focus, so that enter can be pressed repeatedly
+                    response.request_focus();
+                } else if escape {
+                    search.query.clear();
+                }
+            }
+
+            if response.changed() {
+                // start again from the first match
+                search.current = None;
+                navigation = Some(false);
+            }
+
+            let matches = search.find(inputs, find);
+
+            ui.add_enabled_ui(!matches.cells.is_empty(), |ui| {
+                if ui
+                    .button("⬆")
+                    .on_hover_text(strings.previous_match())
+                    .clicked()
+                {
+                    navigation = Some(true);
+                }
+                if ui.button("⬇").on_hover_text(strings.next_match()).clicked() {
+                    navigation = Some(false);
+                }
+            });
+
+            let scroll_to =
+                navigation.and_then(|backwards| matches.step(search.current, backwards));
+            if scroll_to.is_some() || search.query.is_empty() {
+                search.current = scroll_to;
+            }
+
+            match matches.position(search.current) {
+                Some(position) => ui.label(format!("{}/{}", position + 1, matches.cells.len())),
+                None => ui.label(format!("{}", matches.cells.len())),
+            };
+
+            ui.toggle_value(&mut search.filter_to_matches, strings.filter_to_matches());
+
+            (matches, scroll_to)
+        })
+        .inner
+    }
+
+    /// Returns `Some` if the filter was changed, containing the new filter, if any.
+    ///
+    /// `id` identifies the column, the popup's working state is kept in memory using it.
+    fn show_filter_popup(
+        ui: &mut Ui,
+        id: Id,
+        strings: &dyn DeferredTableStrings,
+        kind: &FilterKind,
+        filter: Option<&ColumnFilter>,
+    ) -> Option<Option<ColumnFilter>> {
+        let new_filter = match kind {
+            FilterKind::Text => {
+                // there is no filter while the text is empty, so the operator and case-sensitivity are kept in memory,
+                // the working state is discarded if the filter was changed elsewhere, e.g. via `DeferredTableState`.
+                let working_state_id = id.with("text_filter");
+                let text_filter = |working_state: &ColumnFilter| match working_state {
+                    ColumnFilter::Text { text, .. } if text.is_empty() => None,
+                    working_state => Some(working_state.clone()),
+                };
+                let working_state = ui
+                    .data(|data| data.get_temp::<ColumnFilter>(working_state_id))
+                    .filter(|working_state| text_filter(working_state).as_ref() == filter)
+                    .or_else(|| filter.cloned());
+                let (mut operator, mut text, mut case_sensitive) = match working_state {
+                    Some(ColumnFilter::Text {
+                        operator,
+                        text,
+                        case_sensitive,
+                    }) => (operator, text, case_sensitive),
+                    _ => Default::default(),
+                };
+
+                ui.horizontal(|ui| {
+                    ui.selectable_value(
+                        &mut operator,
+                        TextFilterOperator::Contains,
+                        strings.filter_contains(),
+                    );
+                    ui.selectable_value(
+                        &mut operator,
+                        TextFilterOperator::Equals,
+                        strings.filter_equals(),
+                    );
+                    ui.selectable_value(
+                        &mut operator,
+                        TextFilterOperator::Regex,
+                        strings.filter_regex(),
+                    );
+                });
+                ui.horizontal(|ui| {
+                    ui.text_edit_singleline(&mut text);
+                    ui.toggle_value(&mut case_sensitive, strings.filter_case_sensitive());
+                });
+
+                let working_state = ColumnFilter::Text {
+                    operator,
+                    text,
+                    case_sensitive,
+                };
+                if let Some(error) = working_state.error() {
+                    ui.colored_label(ui.visuals().error_fg_color, error);
+                }
+
+                let new_filter = text_filter(&working_state);
+                ui.data_mut(|data| data.insert_temp(working_state_id, working_state));
+                new_filter
+            }
+            FilterKind::Number => {
+                let (mut min, mut max) = match filter {
+                    Some(ColumnFilter::Range { min, max }) => (*min, *max),
+                    _ => (None, None),
+                };
+
+                for (bound, label) in [
+                    (&mut min, strings.filter_minimum()),
+                    (&mut max, strings.filter_maximum()),
+                ] {
+                    ui.horizontal(|ui| {
+                        let mut enabled = bound.is_some();
+                        let mut value = bound.unwrap_or_default();
+                        ui.checkbox(&mut enabled, label);
+                        ui.add_enabled(enabled, egui::DragValue::new(&mut value));
+                        *bound = enabled.then_some(value);
+                    });
+                }
+
+                match (min, max) {
+                    (None, None) => None,
+                    (min, max) => Some(ColumnFilter::Range { min, max }),
+                }
+            }
+            FilterKind::Values(choices) => {
+                let mut selected = match filter {
+                    Some(ColumnFilter::Values(values)) => values.clone(),
+                    _ => choices.iter().cloned().collect(),
+                };
+
+                egui::ScrollArea::vertical()
+                    .max_height(200.0)
+                    .show(ui, |ui| {
+                        for choice in choices {
+                            let mut checked = selected.contains(choice);
+                            if ui.checkbox(&mut checked, choice).changed() {
+                                match checked {
+                                    true => selected.insert(choice.clone()),
+                                    false => selected.remove(choice),
+                                };
+                            }
+                        }
+                    });
+
+                if choices.iter().all(|choice| selected.contains(choice)) {
+                    None
+                } else {
+                    Some(ColumnFilter::Values(selected))
+                }
+            }
+        };
+
+        (new_filter.as_ref() != filter).then_some(new_filter)
+    }
+
     fn build_cell_kind(grid_row_index: usize, grid_column_index: usize) -> CellKind {
         if grid_row_index == 0 && grid_column_index == 0 {
             CellKind::Corner
@@ -1335,15 +3481,50 @@ impl<'a, DataSource> DeferredTable<'a, DataSource> {
         }
     }
 
-    fn map_index(count: usize, row_ordering: &[usize], visible_row_index: usize) -> usize {
-        let mut mapped_row_index = *row_ordering
-            .get(visible_row_index)
-            .unwrap_or(&visible_row_index);
-        if mapped_row_index >= count {
-            // handle out-of-range mapping values
-            mapped_row_index = visible_row_index;
+    /// returns the size of a mapped column/row, including the spacing, or `None` if it's filtered, see [`Pinning`].
+    fn size_of_index<'v>(
+        values: &'v [f32],
+        filter: Option<&'v [usize]>,
+        sizing: f32,
+    ) -> impl Fn(usize) -> Option<f32> + 'v {
+        move |index| {
+            (!filter.is_some_and(|filter| filter.contains(&index))).then(|| values[index] + sizing)
         }
-        mapped_row_index
+    }
+
+    /// returns the scroll offset required to make the row/column fully visible, if the row/column is larger than the
+    /// visible range then the start of the row/column is made visible.
+    ///
+    /// `size` returns the size of a mapped index, including any spacing, or `None` if the index is filtered, `visible` is
+    /// the range of the content currently visible, excluding the headers.
+    fn scroll_offset_for_index(
+        mapped_index: usize,
+        count: usize,
+        ordering: &[usize],
+        size: impl Fn(usize) -> Option<f32>,
+        visible: Range<f32>,
+    ) -> f32 {
+        let mut start = 0.0;
+        for index in visible_indexes(count, ordering, None) {
+            let Some(size) = size(index) else {
+                continue;
+            };
+            if index == mapped_index {
+                let end = start + size;
+                let visible_size = visible.end - visible.start;
+                return if start < visible.start || size > visible_size {
+                    start
+                } else if end > visible.end {
+                    end - visible_size
+                } else {
+                    visible.start
+                };
+            }
+            start += size;
+        }
+
+        // not visible, e.g. filtered
+        visible.start
     }
 
     /// call this function from a cell action handler
@@ -1363,14 +3544,7 @@ impl<'a, DataSource> DeferredTable<'a, DataSource> {
                 trace!("clicked in selected cell");
 
                 // change mode to edit
-                let item_state = editor.build_item_state(cell_index, source);
-                if let Some((edit, original_item)) = item_state {
-                    edit_state.state.replace(CellEditState::Editing(
-                        cell_index,
-                        edit,
-                        original_item,
-                    ));
-                }
+                Self::begin_edit(source, cell_index, editor, edit_state);
             }
             Some(CellEditState::Pivot(_)) => {
                 trace!("clicked in different cell");
@@ -1393,6 +3567,54 @@ impl<'a, DataSource> DeferredTable<'a, DataSource> {
         }
     }
 
+    fn begin_edit<IS, V>(
+        source: &mut DataSource,
+        cell_index: CellIndex,
+        editor: &mut dyn EditableTableRenderer<DataSource, Value = V, ItemState = IS>,
+        edit_state: &mut EditorState<IS, V>,
+    ) {
+        let item_state = editor.build_item_state(cell_index, source);
+        if let Some((edit, original_item)) = item_state {
+            edit_state
+                .state
+                .replace(CellEditState::Editing(cell_index, edit, original_item));
+        }
+    }
+
+    /// returns true if the edit was started, i.e. the cell can be edited.
+    fn begin_edit_from_text<IS, V>(
+        source: &mut DataSource,
+        cell_index: CellIndex,
+        text: &str,
+        editor: &mut dyn EditableTableRenderer<DataSource, Value = V, ItemState = IS>,
+        edit_state: &mut EditorState<IS, V>,
+    ) -> bool {
+        let item_state = editor.build_item_state_from_text(cell_index, text, source);
+        let Some((edit, original_item)) = item_state else {
+            return false;
+        };
+        edit_state
+            .state
+            .replace(CellEditState::Editing(cell_index, edit, original_item));
+        true
+    }
+
+    fn cancel_edit<IS, V>(
+        source: &mut DataSource,
+        cell_index: CellIndex,
+        editor: &mut dyn EditableTableRenderer<DataSource, Value = V, ItemState = IS>,
+        edit_state: &mut EditorState<IS, V>,
+    ) {
+        trace!("cancelling edit");
+        let Some(CellEditState::Editing(index, state, original_item)) = edit_state.state.take()
+        else {
+            unreachable!();
+        };
+        editor.on_edit_cancelled(index, state, original_item, source);
+
+        edit_state.state.replace(CellEditState::Pivot(cell_index));
+    }
+
     fn apply_edit<IS, V>(
         source: &mut DataSource,
         cell_index: CellIndex,
@@ -1405,7 +3627,20 @@ impl<'a, DataSource> DeferredTable<'a, DataSource> {
         else {
             unreachable!();
         };
+        let original = edit_state
+            .history
+            .as_ref()
+            .map(|history| history.clone_value(&original_item));
         editor.on_edit_complete(index, state, original_item, source);
+        if let (Some(history), Some(original)) = (edit_state.history.as_mut(), original) {
+            // edits that didn't change the value, e.g. when no changes were made, are not recorded.
+            let committed = editor
+                .build_item_state(index, source)
+                .map(|(_, value)| value);
+            if !committed.is_some_and(|committed| history.eq_value(&committed, &original)) {
+                history.record(HistoryEntry::Cells(vec![(index, original)]));
+            }
+        }
 
         // change selection
         edit_state.state.replace(CellEditState::Pivot(cell_index));
@@ -1445,6 +3680,7 @@ enum RowKind {
 /// State that could be stored between application restarts
 #[derive(Default, Clone)]
 #[cfg_attr(feature = "persistence", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "persistence", serde(default))]
 struct DeferredTablePersistentState {
     // FUTURE We *could* add row/column ordering/filtering here too
     column_widths: Vec<f32>,
@@ -1476,8 +3712,48 @@ struct DeferredTableTempState {
     // the collection here needs to a have a fast lookup, slow insertion/removal is fine.
     // this is because we render frames often and insert/remove infrequently.
     row_selections: BTreeSet<usize>,
+    /// the mapped index of the last clicked row header, used for shift+click range selection.
+    row_selection_anchor: Option<usize>,
+    /// while the user is dragging a row selection, holds the mapped index of the row the pointer was last over.
+    selecting_rows: Option<usize>,
+    column_selections: BTreeSet<usize>,
+    /// the mapped index of the last clicked column header, used for shift+click range selection.
+    column_selection_anchor: Option<usize>,
+    /// holds the selected cell ranges, the last range is the one being extended by shift+click or dragging.
+    cell_selections: Vec<CellRange>,
+    /// true while the user is dragging a cell selection.
+    selecting_cells: bool,
+    /// holds the mapped indexes of the sorted columns and their sort directions.
+    sort: SortSpec,
+    /// holds the filters, keyed by mapped column index.
+    filters: BTreeMap<usize, ColumnFilter>,
     /// holds the dimensions used on the last render.
     dimensions: Option<TableDimensions>,
+    /// true if the last pointer button press was over the table.
+    focused: bool,
+    search: SearchState,
+    /// holds the mapped index of a cell to scroll to, if needed, so that the cell is fully visible.
+    scroll_to: Option<CellIndex>,
+    /// the cell a pointer button was pressed over, used to detect clicks on cells.
+    cell_press: Option<CellPress>,
+    /// the mapped index of the active cell, moved by clicking or by the keyboard.  When an editor is used, this is the
+    /// same as the cell of the edit state.
+    active_cell: Option<CellIndex>,
+    /// the widths of the contents of the column headers and cells shown on the last render, keyed by mapped column
+    /// index, used for auto-fitting columns.
+    content_widths: BTreeMap<usize, f32>,
+    /// holds the mapped indexes of the collapsed rows, see `DeferredTableDataSource::tree_row`.
+    collapsed_rows: BTreeSet<usize>,
+    /// the descendants of the collapsed rows, which are filtered.
+    collapsed_rows_filter: Memo<Vec<usize>>,
+    /// holds the keys of the collapsed row groups, see `DeferredTable::group_rows_by`.
+    collapsed_groups: BTreeSet<String>,
+    /// the row groups, see `DeferredTable::group_rows_by`.
+    row_groups: Memo<RowGroups>,
+    /// the rows of the collapsed row groups, which are filtered.
+    collapsed_groups_filter: Memo<Vec<usize>>,
+    /// the rows and the group header rows, as laid out, see `RowLayout`.
+    row_layout: Memo<RowLayout>,
 }
 
 #[derive(Clone, Copy)]
@@ -1486,6 +3762,8 @@ struct DragState {
     start_pos: Pos2,
     cell_kind: CellKind,
     initial_size: f32,
+    /// the width/height of the column/row when dragging started, excluding the cell margins, for the edit history.
+    initial_inner_size: f32,
 }
 
 impl DeferredTableTempState {
@@ -0,0 +1,121 @@
+use std::fmt::{Debug, Formatter};
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::Arc;
+
+/// A value computed from the data source, kept between frames until its inputs or the data revision change, see
+/// [`crate::DeferredTableDataSource::data_revision`].
+///
+/// Only a hash of the inputs is kept, and the value is shared, so that the table's state can be cloned each frame
+/// without copying the value or its inputs.
+pub(crate) struct Memo<V> {
+    entry: Option<MemoEntry<V>>,
+}
+
+struct MemoEntry<V> {
+    /// the hash of the revision and the inputs, `None` if there was no revision, in which case the value is never
+    /// re-used.
+    key: Option<u64>,
+    value: Arc<V>,
+}
+
+impl<V> Memo<V> {
+    /// returns the value for the inputs, `compute` is only called when the revision or the inputs have changed since
+    /// the last call, or when `revision` is `None`, i.e. the data source doesn't track changes.
+    pub(crate) fn get(
+        &mut self,
+        revision: Option<u64>,
+        inputs: impl Hash,
+        compute: impl FnOnce() -> V,
+    ) -> Arc<V> {
+        let key = revision.map(|revision| {
+            let mut hasher = DefaultHasher::new();
+            (revision, inputs).hash(&mut hasher);
+            hasher.finish()
+        });
+        if let Some(entry) = &self.entry
+            && key.is_some()
+            && entry.key == key
+        {
+            return entry.value.clone();
+        }
+
+        let entry = self.entry.insert(MemoEntry {
+            key,
+            value: Arc::new(compute()),
+        });
+        entry.value.clone()
+    }
+}
+
+impl<V> Default for Memo<V> {
+    fn default() -> Self {
+        Self { entry: None }
+    }
+}
+
+impl<V> Clone for Memo<V> {
+    fn clone(&self) -> Self {
+        Self {
+            entry: self.entry.as_ref().map(|entry| MemoEntry {
+                key: entry.key,
+                value: entry.value.clone(),
+            }),
+        }
+    }
+}
+
+impl<V> Debug for Memo<V> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Memo")
+            .field("key", &self.entry.as_ref().map(|entry| entry.key))
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod memo_tests {
+    use crate::memo::Memo;
+    use std::cell::Cell;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_get() {
+        // given
+        let mut memo = Memo::default();
+        let calls = Cell::new(0);
+        let compute = || {
+            calls.set(calls.get() + 1);
+            calls.get()
+        };
+
+        // when
+        let value = memo.get(Some(1), &[0, 1][..], compute);
+        let same_value = memo.get(Some(1), &[0, 1][..], compute);
+        memo.get(Some(1), &[1, 0][..], compute);
+        let value_for_revision = memo.get(Some(2), &[1, 0][..], compute);
+
+        // then
+        assert!(Arc::ptr_eq(&value, &same_value));
+        assert_eq!(calls.get(), 3);
+        assert_eq!(*value_for_revision, 3);
+    }
+
+    #[test]
+    fn test_get_without_revision() {
+        // given
+        let mut memo = Memo::default();
+        let calls = Cell::new(0);
+        let compute = || {
+            calls.set(calls.get() + 1);
+            calls.get()
+        };
+
+        // when
+        memo.get(None, (), compute);
+        let value = memo.get(None, (), compute);
+
+        // then
+        assert_eq!(calls.get(), 2);
+        assert_eq!(*value, 2);
+    }
+}
@@ -0,0 +1,181 @@
+use crate::CellIndex;
+use egui::{InputState, Key, Modifiers};
+
+/// A keyboard movement of the active cell, see [`crate::Action::ActiveCellChanged`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Navigation {
+    Up,
+    Down,
+    Left,
+    Right,
+    /// the first visible column of the row
+    RowStart,
+    /// the last visible column of the row
+    RowEnd,
+    /// the first visible cell
+    First,
+    /// the last visible cell
+    Last,
+    PageUp,
+    PageDown,
+}
+
+impl Navigation {
+    /// consumes the first navigation key that was pressed, if any.
+    pub(crate) fn consume(input: &mut InputState) -> Option<Self> {
+        const KEYS: [(Modifiers, Key, Navigation); 10] = [
+            (Modifiers::NONE, Key::ArrowUp, Navigation::Up),
+            (Modifiers::NONE, Key::ArrowDown, Navigation::Down),
+            (Modifiers::NONE, Key::ArrowLeft, Navigation::Left),
+            (Modifiers::NONE, Key::ArrowRight, Navigation::Right),
+            (Modifiers::COMMAND, Key::Home, Navigation::First),
+            (Modifiers::COMMAND, Key::End, Navigation::Last),
+            (Modifiers::NONE, Key::Home, Navigation::RowStart),
+            (Modifiers::NONE, Key::End, Navigation::RowEnd),
+            (Modifiers::NONE, Key::PageUp, Navigation::PageUp),
+            (Modifiers::NONE, Key::PageDown, Navigation::PageDown),
+        ];
+
+        KEYS.iter()
+            .find(|(modifiers, key, _)| input.consume_key(*modifiers, *key))
+            .map(|(_, _, navigation)| *navigation)
+    }
+
+    /// Returns the new active cell, or `None` if there are no visible cells.
+    ///
+    /// `visible_rows` and `visible_columns` are the mapped indexes in visible order, excluding any filtered indexes.
+    /// When there is no active cell, or it is no-longer visible, the first visible cell is used.
+    ///
+    /// `row_height` returns the height of a row, including any spacing, and `page_height` is the height of the visible
+    /// cells, both are used for `PageUp`/`PageDown`, which always move at least one row.
+    pub(crate) fn apply(
+        self,
+        active_cell: Option<CellIndex>,
+        visible_rows: &[usize],
+        visible_columns: &[usize],
+        row_height: impl Fn(usize) -> f32,
+        page_height: f32,
+    ) -> Option<CellIndex> {
+        let (last_row, last_column) = (
+            visible_rows.len().checked_sub(1)?,
+            visible_columns.len().checked_sub(1)?,
+        );
+
+        let position = active_cell.and_then(|cell_index| {
+            Some((
+                visible_rows.iter().position(|row| *row == cell_index.row)?,
+                visible_columns
+                    .iter()
+                    .position(|column| *column == cell_index.column)?,
+            ))
+        });
+        let Some((row, column)) = position else {
+            return Some(CellIndex {
+                row: visible_rows[0],
+                column: visible_columns[0],
+            });
+        };
+
+        let page = |forwards: bool| {
+            let mut target = row;
+            let mut remaining = page_height;
+            loop {
+                let next = match forwards {
+                    true if target < last_row => target + 1,
+                    false if target > 0 => target - 1,
+                    _ => break,
+                };
+                remaining -= row_height(visible_rows[next]);
+                // always move at least one row
+                if remaining < 0.0 && target != row {
+                    break;
+                }
+                target = next;
+            }
+            target
+        };
+
+        let (row, column) = match self {
+            Navigation::Up => (row.saturating_sub(1), column),
+            Navigation::Down => ((row + 1).min(last_row), column),
+            Navigation::Left => (row, column.saturating_sub(1)),
+            Navigation::Right => (row, (column + 1).min(last_column)),
+            Navigation::RowStart => (row, 0),
+            Navigation::RowEnd => (row, last_column),
+            Navigation::First => (0, 0),
+            Navigation::Last => (last_row, last_column),
+            Navigation::PageUp => (page(false), column),
+            Navigation::PageDown => (page(true), column),
+        };
+
+        Some(CellIndex {
+            row: visible_rows[row],
+            column: visible_columns[column],
+        })
+    }
+}
+
+#[cfg(test)]
+mod navigation_tests {
+    use crate::CellIndex;
+    use crate::navigation::Navigation;
+    use rstest::rstest;
+
+    // visible order, with row 2 and column 1 filtered.
+    const VISIBLE_ROWS: [usize; 5] = [4, 0, 1, 3, 5];
+    const VISIBLE_COLUMNS: [usize; 3] = [2, 0, 3];
+
+    fn cell(row: usize, column: usize) -> CellIndex {
+        CellIndex { row, column }
+    }
+
+    #[rstest]
+    #[case(None, Navigation::Down, Some(cell(4, 2)))]
+    // filtered active cell
+    #[case(Some(cell(2, 0)), Navigation::Down, Some(cell(4, 2)))]
+    #[case(Some(cell(0, 0)), Navigation::Up, Some(cell(4, 0)))]
+    #[case(Some(cell(4, 0)), Navigation::Up, Some(cell(4, 0)))]
+    #[case(Some(cell(1, 0)), Navigation::Down, Some(cell(3, 0)))]
+    #[case(Some(cell(5, 0)), Navigation::Down, Some(cell(5, 0)))]
+    #[case(Some(cell(0, 0)), Navigation::Left, Some(cell(0, 2)))]
+    #[case(Some(cell(0, 0)), Navigation::Right, Some(cell(0, 3)))]
+    #[case(Some(cell(0, 3)), Navigation::Right, Some(cell(0, 3)))]
+    #[case(Some(cell(0, 0)), Navigation::RowStart, Some(cell(0, 2)))]
+    #[case(Some(cell(0, 0)), Navigation::RowEnd, Some(cell(0, 3)))]
+    #[case(Some(cell(1, 0)), Navigation::First, Some(cell(4, 2)))]
+    #[case(Some(cell(1, 0)), Navigation::Last, Some(cell(5, 3)))]
+    // a page is 2 rows
+    #[case(Some(cell(4, 0)), Navigation::PageDown, Some(cell(1, 0)))]
+    #[case(Some(cell(1, 0)), Navigation::PageDown, Some(cell(5, 0)))]
+    #[case(Some(cell(5, 0)), Navigation::PageUp, Some(cell(1, 0)))]
+    #[case(Some(cell(0, 0)), Navigation::PageUp, Some(cell(4, 0)))]
+    fn test_apply(
+        #[case] active_cell: Option<CellIndex>,
+        #[case] navigation: Navigation,
+        #[case] expected: Option<CellIndex>,
+    ) {
+        let active_cell =
+            navigation.apply(active_cell, &VISIBLE_ROWS, &VISIBLE_COLUMNS, |_| 10.0, 25.0);
+        assert_eq!(active_cell, expected);
+    }
+
+    #[test]
+    fn test_page_moves_at_least_one_row() {
+        let active_cell = Navigation::PageDown.apply(
+            Some(cell(4, 0)),
+            &VISIBLE_ROWS,
+            &VISIBLE_COLUMNS,
+            |_| 100.0,
+            25.0,
+        );
+        assert_eq!(active_cell, Some(cell(0, 0)));
+    }
+
+    #[test]
+    fn test_no_visible_cells() {
+        assert_eq!(
+            Navigation::Down.apply(Some(cell(0, 0)), &[], &VISIBLE_COLUMNS, |_| 10.0, 25.0),
+            None
+        );
+    }
+}
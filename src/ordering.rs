@@ -1,3 +1,7 @@
+use crate::{ColumnComparator, SortDirection, SortSpec};
+use std::cmp::Ordering;
+use std::collections::BTreeSet;
+
 /// Helper method to be used by clients to help with handling column re-ordering during action processing.
 ///
 /// ```text
@@ -54,6 +58,164 @@ pub fn apply_reordering(ordering: &mut Option<Vec<usize>>, from: usize, to: usiz
     ordering.insert(to_pos, from);
 }
 
+/// Helper method to be used by clients to help with handling column group re-ordering during action processing.
+///
+/// Moves all the `indexes` next to `to`, keeping their relative order, after `to` if the first of the indexes was
+/// before it, otherwise before it.  Nothing is moved if `to` is one of the indexes.
+///
+/// ```text
+/// match action {
+///     Action::ColumnGroupReorder { group, to } => {
+///         egui_deferred_table::apply_group_reordering(&mut column_ordering, &column_groups[group].columns, to);
+///     }
+///     // ...
+/// }
+///```
+///
+/// See also:
+/// 1. [`crate::ColumnGroup`]
+/// 2. [`crate::Action::ColumnGroupReorder`]
+///
+pub fn apply_group_reordering(ordering: &mut Option<Vec<usize>>, indexes: &[usize], to: usize) {
+    if indexes.is_empty() || indexes.contains(&to) {
+        return;
+    }
+
+    let ordering = ordering.get_or_insert_with(Vec::new);
+
+    // Expand the vector if needed to include all the indexes
+    let max_index = indexes.iter().copied().fold(to, usize::max);
+    while ordering.len() <= max_index {
+        ordering.push(ordering.len());
+    }
+
+    let to_pos = ordering.iter().position(|&x| x == to).unwrap();
+    let first_pos = ordering.iter().position(|x| indexes.contains(x)).unwrap();
+
+    let (moved, mut remaining): (Vec<usize>, Vec<usize>) =
+        ordering.iter().partition(|x| indexes.contains(x));
+
+    let mut insert_pos = remaining.iter().position(|&x| x == to).unwrap();
+    if first_pos < to_pos {
+        insert_pos += 1;
+    }
+    remaining.splice(insert_pos..insert_pos, moved);
+
+    *ordering = remaining;
+}
+
+/// Helper method to be used by clients to build a row ordering when handling a sort action.
+///
+/// The `key` closure is called once for each row index in `0..count`, the returned keys are then used to sort the
+/// rows.  Rows with equal keys retain their original relative order.  Keys that cannot be compared (e.g. `f32::NAN`)
+/// are treated as equal.
+///
+/// ```text
+/// match action {
+///     Action::SortChanged { column, direction, .. } => {
+///         row_ordering = direction.map(|direction| {
+///             egui_deferred_table::build_sort_ordering(rows.len(), direction, |index| rows[index].get(column))
+///         });
+///     }
+///     // ...
+/// }
+///```
+///
+/// See also:
+/// 1. [`crate::DeferredTableRenderer::row_ordering`]
+/// 2. [`crate::Action::SortChanged`]
+///
+pub fn build_sort_ordering<K: PartialOrd>(
+    count: usize,
+    direction: SortDirection,
+    key: impl FnMut(usize) -> K,
+) -> Vec<usize> {
+    let keys = (0..count).map(key).collect::<Vec<_>>();
+
+    let mut ordering = (0..count).collect::<Vec<_>>();
+    // Note: `sort_by` is a stable sort
+    ordering.sort_by(|a, b| {
+        let ordering = keys[*a].partial_cmp(&keys[*b]).unwrap_or(Ordering::Equal);
+        match direction {
+            SortDirection::Ascending => ordering,
+            SortDirection::Descending => ordering.reverse(),
+        }
+    });
+
+    ordering
+}
+
+/// Helper method to be used by clients to build a row ordering for a multi-column sort.
+///
+/// Rows are compared using each column in the `spec`, in priority order, until the rows are not equal.  Rows that are
+/// equal for all the columns retain their original relative order.  An empty `spec` results in the natural ordering.
+///
+/// ```text
+/// match action {
+///     Action::SortChanged { spec, .. } => {
+///         row_ordering = Some(egui_deferred_table::build_sort_spec_ordering(rows.len(), &spec, &|column, a, b| {
+///             rows[a].get(column).cmp(&rows[b].get(column))
+///         }));
+///     }
+///     // ...
+/// }
+///```
+///
+/// See also:
+/// 1. [`crate::DeferredTableRenderer::row_ordering`]
+/// 2. [`crate::Action::SortChanged`]
+///
+pub fn build_sort_spec_ordering(
+    count: usize,
+    spec: &SortSpec,
+    comparator: &impl ColumnComparator,
+) -> Vec<usize> {
+    let mut ordering = (0..count).collect::<Vec<_>>();
+    // Note: `sort_by` is a stable sort
+    ordering.sort_by(|a, b| {
+        spec.keys()
+            .iter()
+            .map(|(column, direction)| {
+                let ordering = comparator.compare(*column, *a, *b);
+                match direction {
+                    SortDirection::Ascending => ordering,
+                    SortDirection::Descending => ordering.reverse(),
+                }
+            })
+            .find(|ordering| !ordering.is_eq())
+            .unwrap_or(Ordering::Equal)
+    });
+
+    ordering
+}
+
+/// returns the mapped index of a visible index, visible indexes outside the ordering, or mapped to an out-of-range
+/// index, are not re-ordered.
+pub(crate) fn map_index(count: usize, ordering: &[usize], visible_index: usize) -> usize {
+    let mut mapped_index = *ordering.get(visible_index).unwrap_or(&visible_index);
+    if mapped_index >= count {
+        // handle out-of-range mapping values
+        mapped_index = visible_index;
+    }
+    mapped_index
+}
+
+/// returns the mapped indexes, in visible order, excluding filtered indexes.
+pub(crate) fn visible_indexes<'o>(
+    count: usize,
+    ordering: &'o [usize],
+    filter: Option<&[usize]>,
+) -> impl Iterator<Item = usize> + 'o {
+    let filter = filter.map(|filter| filter.iter().copied().collect::<BTreeSet<_>>());
+    (0..count)
+        .map(move |visible_index| map_index(count, ordering, visible_index))
+        .filter(move |mapped_index| {
+            !filter
+                .as_ref()
+                .is_some_and(|filter| filter.contains(mapped_index))
+        })
+}
+
 #[cfg(test)]
 mod reordering_tests {
     use crate::ordering::apply_reordering;
@@ -85,3 +247,132 @@ mod reordering_tests {
         assert_eq!(ordering, Some(expected));
     }
 }
+
+#[cfg(test)]
+mod group_reordering_tests {
+    use crate::ordering::apply_group_reordering;
+    use rstest::rstest;
+
+    #[rstest]
+    // dragging right
+    #[case(&[1,2], 4, vec![], vec![0,3,4,1,2])]
+    // dragging left
+    #[case(&[3,4], 1, vec![], vec![0,3,4,1,2])]
+    // non-adjacent indexes are joined, keeping their order
+    #[case(&[4,1], 5, vec![0,4,2,1,3,5], vec![0,2,3,5,4,1])]
+    #[case(&[1,3], 2, vec![], vec![0,2,1,3])]
+    // onto one of the indexes
+    #[case(&[1,2], 2, vec![], vec![])]
+    fn test_apply_group_reordering(
+        #[case] indexes: &[usize],
+        #[case] to: usize,
+        #[case] ordering: Vec<usize>,
+        #[case] expected: Vec<usize>,
+    ) {
+        let mut ordering = Some(ordering);
+        apply_group_reordering(&mut ordering, indexes, to);
+        assert_eq!(ordering, Some(expected));
+    }
+}
+
+#[cfg(test)]
+mod sort_ordering_tests {
+    use crate::SortDirection;
+    use crate::ordering::build_sort_ordering;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(SortDirection::Ascending, vec![], vec![])]
+    #[case(SortDirection::Ascending, vec![3,1,2], vec![1,2,0])]
+    #[case(SortDirection::Descending, vec![3,1,2], vec![0,2,1])]
+    // equal keys retain their original order, regardless of direction
+    #[case(SortDirection::Ascending, vec![2,1,2,1], vec![1,3,0,2])]
+    #[case(SortDirection::Descending, vec![2,1,2,1], vec![0,2,1,3])]
+    fn test_build_sort_ordering(
+        #[case] direction: SortDirection,
+        #[case] keys: Vec<u32>,
+        #[case] expected: Vec<usize>,
+    ) {
+        let ordering = build_sort_ordering(keys.len(), direction, |index| keys[index]);
+        assert_eq!(ordering, expected);
+    }
+}
+
+#[cfg(test)]
+mod sort_spec_ordering_tests {
+    use crate::SortDirection::{Ascending, Descending};
+    use crate::ordering::build_sort_spec_ordering;
+    use crate::{SortDirection, SortSpec};
+    use rstest::rstest;
+
+    const ROWS: [(&str, u32, &str); 5] = [
+        ("0805", 100, "R3"),
+        ("0603", 100, "R1"),
+        ("0805", 10, "R2"),
+        ("0603", 220, "R4"),
+        ("0805", 100, "R5"),
+    ];
+
+    #[rstest]
+    #[case(vec![], vec![0,1,2,3,4])]
+    #[case(vec![(0, Ascending)], vec![1,3,0,2,4])]
+    #[case(vec![(0, Descending)], vec![0,2,4,1,3])]
+    #[case(vec![(0, Ascending), (1, Ascending)], vec![1,3,2,0,4])]
+    #[case(vec![(0, Ascending), (1, Descending)], vec![3,1,0,4,2])]
+    #[case(vec![(0, Ascending), (1, Ascending), (2, Descending)], vec![1,3,2,4,0])]
+    // equal for all columns, original order retained
+    #[case(vec![(1, Ascending)], vec![2,0,1,4,3])]
+    fn test_build_sort_spec_ordering(
+        #[case] keys: Vec<(usize, SortDirection)>,
+        #[case] expected: Vec<usize>,
+    ) {
+        let spec = SortSpec::from(keys);
+        let ordering =
+            build_sort_spec_ordering(ROWS.len(), &spec, &|column, a: usize, b: usize| {
+                let (row_a, row_b) = (&ROWS[a], &ROWS[b]);
+                match column {
+                    0 => row_a.0.cmp(row_b.0),
+                    1 => row_a.1.cmp(&row_b.1),
+                    2 => row_a.2.cmp(row_b.2),
+                    _ => unreachable!(),
+                }
+            });
+        assert_eq!(ordering, expected);
+    }
+}
+
+#[cfg(test)]
+mod visible_index_tests {
+    use crate::ordering::{map_index, visible_indexes};
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(&[], 2, 2)]
+    #[case(&[3, 2, 1, 0], 1, 2)]
+    // visible indexes outside the ordering are not re-ordered
+    #[case(&[1, 0], 3, 3)]
+    // out-of-range mapped indexes are not re-ordered
+    #[case(&[9, 0], 0, 0)]
+    fn test_map_index(
+        #[case] ordering: &[usize],
+        #[case] visible_index: usize,
+        #[case] expected: usize,
+    ) {
+        assert_eq!(map_index(4, ordering, visible_index), expected);
+    }
+
+    #[rstest]
+    #[case(&[], None, vec![0, 1, 2, 3])]
+    #[case(&[3, 2, 1, 0], None, vec![3, 2, 1, 0])]
+    #[case(&[3, 2, 1, 0], Some(vec![2, 0]), vec![3, 1])]
+    fn test_visible_indexes(
+        #[case] ordering: &[usize],
+        #[case] filter: Option<Vec<usize>>,
+        #[case] expected: Vec<usize>,
+    ) {
+        assert_eq!(
+            visible_indexes(4, ordering, filter.as_deref()).collect::<Vec<_>>(),
+            expected
+        );
+    }
+}
@@ -1,3 +1,4 @@
+use crate::{ColumnGroup, CopyFormat, DefaultStrings, DeferredTableStrings, FilterKind, Pin};
 use egui::{NumExt, Rangef, Vec2};
 
 /// Specifies the axis (row/column) parameters.
@@ -17,6 +18,9 @@ pub struct AxisParameters {
     pub resizable: bool,
     pub monospace: bool,
     pub expandable: bool,
+    pub sortable: bool,
+    pub filter: Option<FilterKind>,
+    pub pinned: Option<Pin>,
 }
 
 impl Default for AxisParameters {
@@ -28,6 +32,9 @@ impl Default for AxisParameters {
             resizable: true,
             monospace: false,
             expandable: false,
+            sortable: true,
+            filter: None,
+            pinned: None,
         }
     }
 }
@@ -85,6 +92,40 @@ impl AxisParameters {
         self.expandable = value;
         self
     }
+
+    /// indicates if the user can sort the table by clicking on this column's header.
+    ///
+    /// Only applicable when sorting is enabled on the table, see [`crate::DeferredTable::sortable_columns`].
+    ///
+    /// Currently not applicable to rows.
+    pub fn sortable(mut self, value: bool) -> Self {
+        self.sortable = value;
+        self
+    }
+
+    /// adds a filter button to this column's header, the button opens a popup appropriate for the kind of filter.
+    ///
+    /// See [`crate::Action::FilterChanged`].
+    ///
+    /// Currently not applicable to rows.
+    pub fn filter(mut self, kind: FilterKind) -> Self {
+        self.filter = Some(kind);
+        self
+    }
+
+    /// keeps this column/row visible when scrolling, at the left/top for [`Pin::Start`], or at the right/bottom for
+    /// [`Pin::End`].
+    ///
+    /// Pinned columns/rows are shown in the order of the column/row ordering, before or after the other columns/rows.
+    /// Columns/rows can only be re-ordered by dragging them onto columns/rows with the same pin.
+    ///
+    /// See also:
+    /// 1. [`crate::DeferredTable::frozen_columns`]
+    /// 2. [`crate::Action::ColumnPinChanged`]
+    pub fn pinned(mut self, pin: Pin) -> Self {
+        self.pinned = Some(pin);
+        self
+    }
 }
 
 pub(crate) struct DeferredTableParameters<'a> {
@@ -97,6 +138,19 @@ pub(crate) struct DeferredTableParameters<'a> {
     /// Can contain fewer entries than the number of rows. Default axis parameters are used for the remaining rows.
     pub(crate) row_parameters: Option<&'a Vec<AxisParameters>>,
     pub(crate) selectable_rows: bool,
+    pub(crate) drag_select_rows: bool,
+    pub(crate) selectable_cells: bool,
+    pub(crate) selectable_columns: bool,
+    pub(crate) sortable_columns: bool,
+    pub(crate) search_bar: bool,
+    pub(crate) edit_on_double_click: bool,
+    pub(crate) column_header_menu: bool,
+    pub(crate) column_chooser: bool,
+    pub(crate) copy_format: CopyFormat,
+    pub(crate) frozen_columns: usize,
+    pub(crate) frozen_rows: usize,
+    pub(crate) column_groups: &'a [ColumnGroup],
+    pub(crate) strings: &'a dyn DeferredTableStrings,
 }
 
 impl<'a> Default for DeferredTableParameters<'a> {
@@ -110,6 +164,19 @@ impl<'a> Default for DeferredTableParameters<'a> {
             column_parameters: None,
             row_parameters: None,
             selectable_rows: true,
+            drag_select_rows: false,
+            selectable_cells: false,
+            selectable_columns: false,
+            sortable_columns: false,
+            search_bar: false,
+            edit_on_double_click: false,
+            column_header_menu: false,
+            column_chooser: false,
+            copy_format: CopyFormat::Tsv,
+            frozen_columns: 0,
+            frozen_rows: 0,
+            column_groups: &[],
+            strings: &DefaultStrings,
         }
     }
 }
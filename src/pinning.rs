@@ -0,0 +1,244 @@
+use crate::Pin;
+use crate::ordering::{map_index, visible_indexes};
+use egui::Rangef;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// The pinned columns/rows of an axis, using mapped indexes.
+///
+/// Pinned indexes are shown in bands which don't scroll, the pinned-start indexes before the scrolled indexes and the
+/// pinned-end indexes after them.  The table uses [`Pinning::ordering`] instead of the renderer's ordering, so that the
+/// visible order is the order in which the indexes are shown.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub(crate) struct Pinning {
+    /// the pinned-start indexes, in visible order, including filtered indexes.
+    start: Vec<usize>,
+    /// the pinned-end indexes, in visible order, including filtered indexes.
+    end: Vec<usize>,
+    /// the ordering with the pinned-start indexes first and the pinned-end indexes last, `None` if nothing is pinned.
+    ordering: Option<Vec<usize>>,
+}
+
+impl Pinning {
+    /// `frozen` is the number of leading, non-filtered, indexes to pin to the start, in addition to the indexes in `pins`.
+    pub(crate) fn new(
+        count: usize,
+        ordering: &[usize],
+        filter: Option<&[usize]>,
+        frozen: usize,
+        pins: impl IntoIterator<Item = (usize, Pin)>,
+    ) -> Self {
+        let pins = pins.into_iter().collect::<BTreeMap<_, _>>();
+        if pins.is_empty() && frozen == 0 {
+            return Self::default();
+        }
+        let filter = filter
+            .unwrap_or_default()
+            .iter()
+            .copied()
+            .collect::<BTreeSet<_>>();
+
+        let mut pinning = Self::default();
+        let mut scrolled = vec![];
+        let mut frozen_remaining = frozen;
+        for index in visible_indexes(count, ordering, None) {
+            let filtered = filter.contains(&index);
+            let index_pin = pins.get(&index).copied().or_else(|| {
+                (!filtered && frozen_remaining > 0).then(|| {
+                    frozen_remaining -= 1;
+                    Pin::Start
+                })
+            });
+            match index_pin {
+                Some(Pin::Start) => pinning.start.push(index),
+                Some(Pin::End) => pinning.end.push(index),
+                None => scrolled.push(index),
+            }
+        }
+
+        if !pinning.start.is_empty() || !pinning.end.is_empty() {
+            pinning.ordering = Some([pinning.start.as_slice(), &scrolled, &pinning.end].concat());
+        }
+        pinning
+    }
+
+    /// the ordering to use instead of `ordering`, the renderer's ordering.
+    pub(crate) fn ordering<'a>(&'a self, ordering: &'a [usize]) -> &'a [usize] {
+        self.ordering.as_deref().unwrap_or(ordering)
+    }
+
+    /// the pin of a mapped index.
+    pub(crate) fn pin(&self, index: usize) -> Option<Pin> {
+        if self.start.contains(&index) {
+            Some(Pin::Start)
+        } else if self.end.contains(&index) {
+            Some(Pin::End)
+        } else {
+            None
+        }
+    }
+
+    /// the range of visible indexes that are scrolled, the pinned-start indexes are before it, the pinned-end indexes
+    /// after it.
+    pub(crate) fn scrolled_range(&self, count: usize) -> std::ops::Range<usize> {
+        self.start.len()..count.saturating_sub(self.end.len()).max(self.start.len())
+    }
+
+    /// the total sizes of the pinned-start and pinned-end bands, `size` returns the size of a mapped index, including
+    /// any spacing, or `None` if the index is filtered.
+    pub(crate) fn band_sizes(&self, size: impl Fn(usize) -> Option<f32>) -> (f32, f32) {
+        let band_size = |indexes: &[usize]| indexes.iter().filter_map(|index| size(*index)).sum();
+        (band_size(&self.start), band_size(&self.end))
+    }
+
+    /// the slots of the grid, see [`GridSlot`], `ordering` is the ordering of the `count` indexes, which has the pinned
+    /// indexes first and last.
+    ///
+    /// slots are used for the `scrolled` visible indexes, which are limited to the scrolled range, followed by slots for
+    /// the non-filtered pinned indexes.  The pinned-start indexes are positioned from `start_position` and the pinned-end
+    /// indexes from `end_position`.  `clip` is the range of the scrolled indexes, i.e. between the bands, the pinned
+    /// indexes are clipped to the range after `start_position`, i.e. after the headers.
+    pub(crate) fn grid_slots(
+        &self,
+        count: usize,
+        ordering: &[usize],
+        scrolled: std::ops::Range<usize>,
+        size: impl Fn(usize) -> Option<f32>,
+        start_position: f32,
+        end_position: f32,
+        clip: Rangef,
+    ) -> Vec<GridSlot> {
+        let scrolled_range = self.scrolled_range(count);
+
+        let mut slots = vec![GridSlot {
+            grid_index: 0,
+            visible_index: scrolled.start,
+            position: None,
+            clip: Rangef::EVERYTHING,
+        }];
+        slots.extend(
+            scrolled
+                .take_while(|visible_index| scrolled_range.contains(visible_index))
+                .enumerate()
+                .map(|(offset, visible_index)| GridSlot {
+                    grid_index: offset + 1,
+                    visible_index,
+                    position: None,
+                    clip,
+                }),
+        );
+
+        let pinned_clip = Rangef::new(start_position, f32::INFINITY);
+        for (visible_indexes, mut position) in [
+            (0..scrolled_range.start, start_position),
+            (scrolled_range.end..count, end_position),
+        ] {
+            for visible_index in visible_indexes {
+                let index = map_index(count, ordering, visible_index);
+                let Some(size) = size(index) else {
+                    continue;
+                };
+                slots.push(GridSlot {
+                    grid_index: slots.len(),
+                    visible_index,
+                    position: Some(position),
+                    clip: pinned_clip,
+                });
+                position += size;
+            }
+        }
+
+        slots
+    }
+}
+
+/// A column/row of the grid of cells that are shown.
+///
+/// The slot with a `grid_index` of 0 is for the headers, the scrolled slots are next, then the pinned slots.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct GridSlot {
+    pub(crate) grid_index: usize,
+    pub(crate) visible_index: usize,
+    /// the x/y of a pinned column/row, `None` for scrolled columns/rows whose position is accumulated.
+    pub(crate) position: Option<f32>,
+    /// the x/y range the cells are clipped to, so scrolled cells are not shown in the pinned bands.
+    pub(crate) clip: Rangef,
+}
+
+#[cfg(test)]
+mod pinning_tests {
+    use crate::Pin;
+    use crate::pinning::{GridSlot, Pinning};
+    use egui::Rangef;
+    use rstest::rstest;
+
+    fn pins() -> [(usize, Pin); 2] {
+        [(3, Pin::Start), (1, Pin::End)]
+    }
+
+    #[rstest]
+    #[case(&[], None, 0, Some(vec![3, 0, 2, 4, 1]), 1, 1)]
+    #[case(&[4, 3, 2, 1, 0], None, 0, Some(vec![3, 4, 2, 0, 1]), 1, 1)]
+    #[case(&[], None, 2, Some(vec![0, 2, 3, 4, 1]), 3, 1)]
+    // filtered indexes are not frozen
+    #[case(&[], Some(vec![0]), 2, Some(vec![2, 3, 4, 0, 1]), 3, 1)]
+    fn test_new(
+        #[case] ordering: &[usize],
+        #[case] filter: Option<Vec<usize>>,
+        #[case] frozen: usize,
+        #[case] expected_ordering: Option<Vec<usize>>,
+        #[case] expected_start: usize,
+        #[case] expected_end: usize,
+    ) {
+        let pinning = Pinning::new(5, ordering, filter.as_deref(), frozen, pins());
+
+        assert_eq!(pinning.ordering, expected_ordering);
+        assert_eq!(pinning.scrolled_range(5), expected_start..5 - expected_end);
+    }
+
+    #[test]
+    fn test_new_without_pins() {
+        let pinning = Pinning::new(5, &[4, 3], None, 0, []);
+
+        assert_eq!(pinning, Pinning::default());
+        assert_eq!(pinning.ordering(&[4, 3]), &[4, 3]);
+        assert_eq!(pinning.scrolled_range(5), 0..5);
+    }
+
+    #[test]
+    fn test_grid_slots() {
+        // given
+        // ordering: 3 (start), 0, 2, 4, 1 (end), where 0 and 2 are scrolled past
+        let pinning = Pinning::new(5, &[], None, 0, pins());
+        let clip = Rangef::new(20.0, 80.0);
+
+        // when
+        // 3 slots are needed to fill the viewport, but the last scrolled slot would be the pinned-end column.
+        let slots = pinning.grid_slots(
+            5,
+            pinning.ordering(&[]),
+            3..6,
+            |_| Some(10.0),
+            5.0,
+            70.0,
+            clip,
+        );
+
+        // then
+        let slot = |grid_index, visible_index, position, clip| GridSlot {
+            grid_index,
+            visible_index,
+            position,
+            clip,
+        };
+        let pinned_clip = Rangef::new(5.0, f32::INFINITY);
+        assert_eq!(
+            slots,
+            vec![
+                slot(0, 3, None, Rangef::EVERYTHING),
+                slot(1, 3, None, clip),
+                slot(2, 0, Some(5.0), pinned_clip),
+                slot(3, 4, Some(70.0), pinned_clip),
+            ]
+        );
+    }
+}
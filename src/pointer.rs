@@ -0,0 +1,197 @@
+use crate::CellIndex;
+use egui::{InputOptions, InputState, PointerButton, Pos2};
+
+/// A click on a cell, i.e. a button was pressed and released over the same cell.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum CellClick {
+    Primary {
+        double: bool,
+    },
+    /// a secondary click, or a long-press on a touch screen, at the position of the pointer.
+    Secondary(Pos2),
+    Middle,
+}
+
+/// Tracks the cell a pointer button was pressed over.
+///
+/// `Response::clicked` and friends cannot be used for cells, since interactive cell contents 'swallow' the clicks.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct CellPress {
+    cell_index: CellIndex,
+    button: PointerButton,
+    /// true once a long-press has been reported, so it's only reported once and not followed by a primary click.
+    long_pressed: bool,
+}
+
+impl CellPress {
+    const BUTTONS: [PointerButton; 3] = [
+        PointerButton::Primary,
+        PointerButton::Secondary,
+        PointerButton::Middle,
+    ];
+
+    /// Call for the cell under the pointer, returns a click if a button was released over the cell it was pressed over.
+    ///
+    /// `press` must be cleared when any button is pressed, before the cells are shown, since the button could be
+    /// pressed outside the cells.
+    pub(crate) fn update(
+        press: &mut Option<CellPress>,
+        cell_index: CellIndex,
+        pos: Pos2,
+        input: &InputState,
+        options: &InputOptions,
+    ) -> Option<CellClick> {
+        let pointer = &input.pointer;
+
+        if let Some(button) = Self::BUTTONS
+            .into_iter()
+            .find(|button| pointer.button_pressed(*button))
+        {
+            *press = Some(CellPress {
+                cell_index,
+                button,
+                long_pressed: false,
+            });
+        }
+
+        let current = press
+            .as_mut()
+            .filter(|current| current.cell_index == cell_index)?;
+
+        // a long-press on a touch screen is equivalent to a secondary click, same as `Response::secondary_clicked`.
+        if current.button == PointerButton::Primary
+            && !current.long_pressed
+            && input.any_touches()
+            && pointer.button_down(PointerButton::Primary)
+            && pointer
+                .press_start_time()
+                .is_some_and(|start_time| input.time - start_time > options.max_click_duration)
+            && pointer
+                .total_drag_delta()
+                .is_some_and(|delta| delta.length() <= options.max_click_dist)
+        {
+            current.long_pressed = true;
+            return Some(CellClick::Secondary(pos));
+        }
+
+        if !pointer.button_released(current.button) {
+            return None;
+        }
+
+        let current = press.take()?;
+        match current.button {
+            PointerButton::Primary if current.long_pressed => None,
+            PointerButton::Primary => Some(CellClick::Primary {
+                double: pointer.button_double_clicked(PointerButton::Primary),
+            }),
+            PointerButton::Secondary => Some(CellClick::Secondary(pos)),
+            PointerButton::Middle => Some(CellClick::Middle),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod cell_press_tests {
+    use crate::CellIndex;
+    use crate::pointer::{CellClick, CellPress};
+    use egui::{Context, Event, Modifiers, PointerButton, Pos2, RawInput};
+
+    const POS: Pos2 = Pos2::new(10.0, 10.0);
+
+    fn cell(row: usize, column: usize) -> CellIndex {
+        CellIndex { row, column }
+    }
+
+    fn button_event(button: PointerButton, pressed: bool) -> Event {
+        Event::PointerButton {
+            pos: POS,
+            button,
+            pressed,
+            modifiers: Modifiers::NONE,
+        }
+    }
+
+    /// runs a frame with the events, calling `update` for the cell.
+    fn frame(
+        ctx: &Context,
+        press: &mut Option<CellPress>,
+        cell_index: CellIndex,
+        events: Vec<Event>,
+    ) -> Option<CellClick> {
+        let mut click = None;
+        let input = RawInput {
+            events: [vec![Event::PointerMoved(POS)], events].concat(),
+            ..RawInput::default()
+        };
+        let _ = ctx.run_ui(input, |ui| {
+            let options = ui.ctx().options(|options| options.input_options);
+            click = ui.input(|input| CellPress::update(press, cell_index, POS, input, &options));
+        });
+        click
+    }
+
+    #[test]
+    fn test_click() {
+        let ctx = Context::default();
+        let mut press = None;
+
+        let pressed = frame(
+            &ctx,
+            &mut press,
+            cell(0, 0),
+            vec![button_event(PointerButton::Primary, true)],
+        );
+        let released = frame(
+            &ctx,
+            &mut press,
+            cell(0, 0),
+            vec![button_event(PointerButton::Primary, false)],
+        );
+
+        assert_eq!(pressed, None);
+        assert_eq!(released, Some(CellClick::Primary { double: false }));
+        assert_eq!(press, None);
+    }
+
+    #[test]
+    fn test_release_over_a_different_cell() {
+        let ctx = Context::default();
+        let mut press = None;
+
+        frame(
+            &ctx,
+            &mut press,
+            cell(0, 0),
+            vec![button_event(PointerButton::Primary, true)],
+        );
+        let released = frame(
+            &ctx,
+            &mut press,
+            cell(1, 0),
+            vec![button_event(PointerButton::Primary, false)],
+        );
+
+        assert_eq!(released, None);
+    }
+
+    #[test]
+    fn test_secondary_and_middle_clicks() {
+        let ctx = Context::default();
+        let mut press = None;
+
+        let clicks = [PointerButton::Secondary, PointerButton::Middle].map(|button| {
+            frame(
+                &ctx,
+                &mut press,
+                cell(0, 0),
+                vec![button_event(button, true), button_event(button, false)],
+            )
+        });
+
+        assert_eq!(
+            clicks,
+            [Some(CellClick::Secondary(POS)), Some(CellClick::Middle)]
+        );
+    }
+}
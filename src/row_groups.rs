@@ -0,0 +1,346 @@
+use crate::ordering::map_index;
+use std::collections::BTreeSet;
+use std::fmt::Display;
+use std::ops::Range;
+
+/// finds the groups of the rows, see [`crate::DeferredTable::group_rows_by`] and [`RowGroups::new`], the arguments are
+/// the data source, the row count, the row ordering, the rows to filter and the scrolled range.
+pub(crate) type GroupRows<'a, DataSource> =
+    Box<dyn Fn(&DataSource, usize, &[usize], Option<&[usize]>, Range<usize>) -> RowGroups + 'a>;
+
+/// returns the aggregates of the rows with the mapped indexes, see [`crate::DeferredTable::group_aggregates`].
+pub(crate) type GroupAggregates<'a, DataSource> = Box<dyn Fn(&DataSource, &[usize]) -> String + 'a>;
+
+/// A run of adjacent rows with the same key.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct RowGroup {
+    /// the key, as text, groups are collapsed by key.
+    pub(crate) key: String,
+    /// the mapped indexes of the rows of the group, in visible order, excluding filtered rows.  The header of the group
+    /// is shown above the first row.
+    pub(crate) rows: Vec<usize>,
+}
+
+/// The groups of the scrolled rows, pinned rows are not grouped, see [`RowLayout`] for the group header rows.
+#[derive(Debug, Default)]
+pub(crate) struct RowGroups {
+    pub(crate) groups: Vec<RowGroup>,
+    /// the index of the group of each row, by mapped row index.
+    row_groups: Vec<Option<usize>>,
+}
+
+impl RowGroups {
+    /// `scrolled` is the range of the visible indexes of the scrolled rows, `key` returns the key of the row with the
+    /// mapped index, the key is only converted to text for the first row of each group.
+    pub(crate) fn new<K: PartialEq + Display>(
+        count: usize,
+        ordering: &[usize],
+        filter: Option<&[usize]>,
+        scrolled: Range<usize>,
+        key: impl Fn(usize) -> K,
+    ) -> Self {
+        let mut row_groups = Self {
+            groups: vec![],
+            row_groups: vec![None; count],
+        };
+        let filter = filter
+            .unwrap_or_default()
+            .iter()
+            .copied()
+            .collect::<BTreeSet<_>>();
+
+        let mut last_key = None;
+        for visible_index in scrolled {
+            let index = map_index(count, ordering, visible_index);
+            if filter.contains(&index) {
+                continue;
+            }
+            let key = key(index);
+            match row_groups.groups.last_mut() {
+                Some(group) if last_key.as_ref() == Some(&key) => group.rows.push(index),
+                _ => {
+                    row_groups.groups.push(RowGroup {
+                        key: key.to_string(),
+                        rows: vec![index],
+                    });
+                    last_key = Some(key);
+                }
+            }
+            row_groups.row_groups[index] = Some(row_groups.groups.len() - 1);
+        }
+
+        row_groups
+    }
+
+    /// returns the index of the group of the row with the mapped index, if any.
+    pub(crate) fn group_of(&self, index: usize) -> Option<usize> {
+        self.row_groups.get(index).copied().flatten()
+    }
+
+    /// returns the index of the group whose header is shown above the row with the mapped index, if any.
+    pub(crate) fn header_of(&self, index: usize) -> Option<usize> {
+        self.group_of(index)
+            .filter(|group_index| self.groups[*group_index].rows[0] == index)
+    }
+
+    /// returns the mapped indexes of the rows to filter, i.e. the rows in `filter` and the rows of the collapsed groups.
+    pub(crate) fn rows_to_filter(
+        &self,
+        collapsed: &BTreeSet<String>,
+        filter: Option<&[usize]>,
+    ) -> Vec<usize> {
+        let mut rows = filter.map(<[usize]>::to_vec).unwrap_or_default();
+        for group in self
+            .groups
+            .iter()
+            .filter(|group| collapsed.contains(&group.key))
+        {
+            rows.extend_from_slice(&group.rows);
+        }
+        rows.sort_unstable();
+        rows
+    }
+}
+
+/// A row of a [`RowLayout`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum LayoutRow {
+    /// a row of the table, with the mapped index.
+    Row(usize),
+    /// the header row of the group with the index.
+    GroupHeader(usize),
+}
+
+/// The rows as they are laid out, i.e. the rows of the table with a group header row above the first row of each group.
+///
+/// The group header rows are virtual rows, whose indexes follow the mapped indexes of the rows, i.e. the header of the
+/// group with the index `g` has the index `count + g`, so the layout's ordering and filter are used in the same way as
+/// the row ordering and the rows to filter.  The rows of collapsed groups are filtered, but their headers are not, and
+/// the headers of the other groups whose rows are all filtered, e.g. by the search bar, are filtered too.
+#[derive(Debug, Default)]
+pub(crate) struct RowLayout {
+    /// the number of rows of the table.
+    row_count: usize,
+    /// the indexes of the rows and group headers, in visible order.
+    pub(crate) ordering: Vec<usize>,
+    /// the indexes of the rows and group headers to filter.
+    pub(crate) filter: Vec<usize>,
+    /// the visible row index of each visible index of the layout, a group header has the visible row index of the first
+    /// row of its group.
+    visible_rows: Vec<usize>,
+    /// the visible row index of the first row of each group.
+    pub(crate) group_starts: Vec<usize>,
+}
+
+impl RowLayout {
+    /// `filter` are the mapped indexes of the rows to filter, including the rows of the collapsed groups, see
+    /// [`RowGroups::rows_to_filter`].
+    pub(crate) fn new(
+        groups: &RowGroups,
+        count: usize,
+        ordering: &[usize],
+        filter: Option<&[usize]>,
+        collapsed: &BTreeSet<String>,
+    ) -> Self {
+        let filter = filter.unwrap_or_default();
+        let mut layout = Self {
+            row_count: count,
+            ordering: Vec::with_capacity(count + groups.groups.len()),
+            filter: filter.to_vec(),
+            visible_rows: Vec::with_capacity(count + groups.groups.len()),
+            group_starts: Vec::with_capacity(groups.groups.len()),
+        };
+
+        for visible_index in 0..count {
+            let index = map_index(count, ordering, visible_index);
+            if let Some(group_index) = groups.header_of(index) {
+                layout.ordering.push(count + group_index);
+                layout.visible_rows.push(visible_index);
+                layout.group_starts.push(visible_index);
+            }
+            layout.ordering.push(index);
+            layout.visible_rows.push(visible_index);
+        }
+
+        let filter = filter.iter().copied().collect::<BTreeSet<_>>();
+        layout.filter.extend(
+            groups
+                .groups
+                .iter()
+                .enumerate()
+                .filter(|(_, group)| {
+                    !collapsed.contains(&group.key)
+                        && group.rows.iter().all(|index| filter.contains(index))
+                })
+                .map(|(group_index, _)| count + group_index),
+        );
+
+        layout
+    }
+
+    /// the number of rows and group headers.
+    pub(crate) fn count(&self) -> usize {
+        self.ordering.len()
+    }
+
+    /// returns the row or group header with the index.
+    pub(crate) fn row(&self, index: usize) -> LayoutRow {
+        match index.checked_sub(self.row_count) {
+            Some(group_index) => LayoutRow::GroupHeader(group_index),
+            None => LayoutRow::Row(index),
+        }
+    }
+
+    /// returns the visible row index of the visible index of the layout, or the row count if the visible index is after
+    /// the last row.
+    pub(crate) fn visible_row_index(&self, visible_index: usize) -> usize {
+        self.visible_rows
+            .get(visible_index)
+            .copied()
+            .unwrap_or(self.row_count)
+    }
+}
+
+#[cfg(test)]
+mod row_group_tests {
+    use crate::CellIndex;
+    use crate::navigation::Navigation;
+    use crate::ordering::visible_indexes;
+    use crate::row_groups::{LayoutRow, RowGroup, RowGroups, RowLayout};
+    use rstest::rstest;
+    use std::collections::BTreeSet;
+
+    const PACKAGES: [&str; 6] = ["0603", "0603", "0805", "0603", "0805", "SOT23"];
+
+    fn group(key: &str, rows: Vec<usize>) -> RowGroup {
+        RowGroup {
+            key: key.to_string(),
+            rows,
+        }
+    }
+
+    #[rstest]
+    #[case(&[], None, 0..6, vec![group("0603", vec![0, 1]), group("0805", vec![2]), group("0603", vec![3]), group("0805", vec![4]), group("SOT23", vec![5])])]
+    // sorted by package
+    #[case(&[0, 1, 3, 2, 4, 5], None, 0..6, vec![group("0603", vec![0, 1, 3]), group("0805", vec![2, 4]), group("SOT23", vec![5])])]
+    // filtered rows don't split the group
+    #[case(&[], Some(&[2][..]), 0..6, vec![group("0603", vec![0, 1, 3]), group("0805", vec![4]), group("SOT23", vec![5])])]
+    // the first and last rows are pinned
+    #[case(&[], None, 1..5, vec![group("0603", vec![1]), group("0805", vec![2]), group("0603", vec![3]), group("0805", vec![4])])]
+    fn test_groups(
+        #[case] ordering: &[usize],
+        #[case] filter: Option<&[usize]>,
+        #[case] scrolled: std::ops::Range<usize>,
+        #[case] expected: Vec<RowGroup>,
+    ) {
+        let row_groups = RowGroups::new(PACKAGES.len(), ordering, filter, scrolled, |index| {
+            PACKAGES[index]
+        });
+        assert_eq!(row_groups.groups, expected);
+    }
+
+    #[test]
+    fn test_header_of() {
+        let row_groups = RowGroups::new(PACKAGES.len(), &[0, 1, 3, 2, 4, 5], None, 0..6, |index| {
+            PACKAGES[index]
+        });
+
+        assert_eq!(row_groups.header_of(0), Some(0));
+        assert_eq!(row_groups.header_of(3), None);
+        assert_eq!(row_groups.group_of(3), Some(0));
+        assert_eq!(row_groups.header_of(2), Some(1));
+        assert_eq!(row_groups.header_of(5), Some(2));
+    }
+
+    #[test]
+    fn test_rows_to_filter() {
+        // given
+        let row_groups = RowGroups::new(PACKAGES.len(), &[0, 1, 3, 2, 4, 5], None, 0..6, |index| {
+            PACKAGES[index]
+        });
+        let collapsed = BTreeSet::from(["0603".to_string()]);
+
+        // when
+        let rows_to_filter = row_groups.rows_to_filter(&collapsed, Some(&[4]));
+
+        // then
+        assert_eq!(rows_to_filter, vec![0, 1, 3, 4]);
+    }
+
+    #[test]
+    fn test_layout() {
+        // given
+        let ordering = [0, 1, 3, 2, 4, 5];
+        let row_groups = RowGroups::new(PACKAGES.len(), &ordering, None, 0..6, |index| {
+            PACKAGES[index]
+        });
+        let collapsed = BTreeSet::from(["0805".to_string()]);
+        let rows_to_filter = row_groups.rows_to_filter(&collapsed, None);
+
+        // when
+        let layout = RowLayout::new(
+            &row_groups,
+            PACKAGES.len(),
+            &ordering,
+            Some(&rows_to_filter),
+            &collapsed,
+        );
+
+        // then
+        // the headers of the groups have the indexes 6, 7 and 8, the header of the collapsed group is not filtered.
+        assert_eq!(layout.ordering, vec![6, 0, 1, 3, 7, 2, 4, 8, 5]);
+        assert_eq!(layout.filter, vec![2, 4]);
+        assert_eq!(layout.count(), 9);
+        assert_eq!(layout.row(3), LayoutRow::Row(3));
+        assert_eq!(layout.row(7), LayoutRow::GroupHeader(1));
+        assert_eq!(layout.visible_row_index(4), 3);
+        assert_eq!(layout.visible_row_index(5), 3);
+        assert_eq!(layout.group_starts, vec![0, 3, 5]);
+    }
+
+    #[test]
+    fn test_layout_filters_headers_of_filtered_groups() {
+        // given
+        let row_groups = RowGroups::new(PACKAGES.len(), &[], None, 0..6, |index| PACKAGES[index]);
+        let collapsed = BTreeSet::from(["0805".to_string()]);
+        let rows_to_filter = row_groups.rows_to_filter(&collapsed, Some(&[0, 1, 3]));
+
+        // when
+        let layout = RowLayout::new(
+            &row_groups,
+            PACKAGES.len(),
+            &[],
+            Some(&rows_to_filter),
+            &collapsed,
+        );
+
+        // then
+        // the first group, and the other group with the same key, have no rows, the collapsed groups are still shown.
+        assert_eq!(layout.filter, vec![0, 1, 2, 3, 4, 6, 8]);
+    }
+
+    #[test]
+    fn test_down_skips_collapsed_group() {
+        // given
+        let ordering = [0, 1, 3, 2, 4, 5];
+        let row_groups = RowGroups::new(PACKAGES.len(), &ordering, None, 0..6, |index| {
+            PACKAGES[index]
+        });
+        let collapsed = BTreeSet::from(["0805".to_string()]);
+        let rows_to_filter = row_groups.rows_to_filter(&collapsed, None);
+        let visible_rows =
+            visible_indexes(PACKAGES.len(), &ordering, Some(&rows_to_filter)).collect::<Vec<_>>();
+
+        // when
+        let active_cell = Navigation::Down.apply(
+            Some(CellIndex { row: 3, column: 0 }),
+            &visible_rows,
+            &[0],
+            |_| 10.0,
+            100.0,
+        );
+
+        // then
+        assert_eq!(active_cell, Some(CellIndex { row: 5, column: 0 }));
+    }
+}
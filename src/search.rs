@@ -0,0 +1,194 @@
+use crate::memo::Memo;
+use crate::{CellIndex, TableDimensions};
+use std::collections::{BTreeSet, HashSet};
+use std::sync::Arc;
+
+/// The state of the search bar, see [`crate::DeferredTable::search_bar`].
+#[derive(Debug, Default, Clone)]
+pub(crate) struct SearchState {
+    pub(crate) query: String,
+    /// when enabled, rows without any matching cells are filtered.
+    pub(crate) filter_to_matches: bool,
+    /// the (mapped) cell index of the current match.
+    pub(crate) current: Option<CellIndex>,
+    /// set when the search bar should take focus on the next frame, e.g. after Ctrl+F.
+    pub(crate) request_focus: bool,
+    /// the results of the last search, see [`SearchState::find`].
+    matches: Memo<SearchMatches>,
+}
+
+/// The inputs of a search, other than the query, the matches are only searched for again when these change.
+#[derive(Hash)]
+pub(crate) struct SearchInputs<'a> {
+    /// see [`crate::DeferredTableDataSource::data_revision`], when `None` the cells are searched every frame.
+    pub(crate) data_revision: Option<u64>,
+    pub(crate) dimensions: TableDimensions,
+    pub(crate) row_ordering: &'a [usize],
+    pub(crate) column_ordering: &'a [usize],
+    pub(crate) rows_to_filter: Option<&'a [usize]>,
+    pub(crate) columns_to_filter: Option<&'a [usize]>,
+}
+
+impl SearchState {
+    /// returns the matches for the query, `find` is only called when the query, the data revision or the inputs have
+    /// changed since the last search.
+    pub(crate) fn find(
+        &mut self,
+        inputs: SearchInputs,
+        find: impl FnOnce(&str) -> SearchMatches,
+    ) -> Arc<SearchMatches> {
+        let query = &self.query;
+        self.matches
+            .get(inputs.data_revision, (query, &inputs), || {
+                let mut matches = find(query);
+                let matching_rows = matches
+                    .cells
+                    .iter()
+                    .map(|cell_index| cell_index.row)
+                    .collect::<BTreeSet<_>>();
+                matches.rows_without_matches = (0..inputs.dimensions.row_count)
+                    .filter(|row| !matching_rows.contains(row))
+                    .collect();
+                matches
+            })
+    }
+}
+
+/// The results of searching the visible cells.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct SearchMatches {
+    /// the (mapped) cell indexes of the matching cells, in visible order (row by row, then column by column).
+    pub(crate) cells: Vec<CellIndex>,
+    /// for fast lookup when rendering cells.
+    lookup: HashSet<CellIndex>,
+    /// the mapped indexes of the rows without any matches, used when filtering to matches, see [`SearchState::find`].
+    pub(crate) rows_without_matches: Vec<usize>,
+}
+
+impl SearchMatches {
+    /// Search the cells, in the order given, for cells with text that contains the query, ignoring case.
+    pub(crate) fn find(
+        query: &str,
+        cells: impl Iterator<Item = CellIndex>,
+        mut text: impl FnMut(CellIndex) -> Option<String>,
+    ) -> Self {
+        if query.is_empty() {
+            return Self::default();
+        }
+
+        let query = query.to_lowercase();
+        let cells = cells
+            .filter(|cell_index| {
+                text(*cell_index).is_some_and(|text| text.to_lowercase().contains(&query))
+            })
+            .collect::<Vec<_>>();
+        let lookup = cells.iter().copied().collect();
+
+        Self {
+            cells,
+            lookup,
+            rows_without_matches: vec![],
+        }
+    }
+
+    pub(crate) fn contains(&self, cell_index: &CellIndex) -> bool {
+        self.lookup.contains(cell_index)
+    }
+
+    /// the zero-based position of the cell in the matches.
+    pub(crate) fn position(&self, cell_index: Option<CellIndex>) -> Option<usize> {
+        cell_index.and_then(|cell_index| self.cells.iter().position(|it| *it == cell_index))
+    }
+
+    /// the match after (or before, when `backwards` is true) the current match, wrapping around at either end.
+    ///
+    /// When there is no current match, or it is no-longer a match, the first (or last) match is used.
+    pub(crate) fn step(&self, current: Option<CellIndex>, backwards: bool) -> Option<CellIndex> {
+        let count = self.cells.len();
+        if count == 0 {
+            return None;
+        }
+
+        let position = match (self.position(current), backwards) {
+            (Some(position), false) => (position + 1) % count,
+            (Some(position), true) => (position + count - 1) % count,
+            (None, false) => 0,
+            (None, true) => count - 1,
+        };
+
+        Some(self.cells[position])
+    }
+}
+
+#[cfg(test)]
+mod search_tests {
+    use crate::search::{SearchInputs, SearchMatches, SearchState};
+    use crate::{CellIndex, TableDimensions};
+    use rstest::rstest;
+
+    const ROWS: [[&str; 2]; 3] = [["R1", "10k"], ["C1", "100nF"], ["R2", "1K"]];
+
+    fn cell(row: usize, column: usize) -> CellIndex {
+        CellIndex { row, column }
+    }
+
+    fn find(query: &str) -> SearchMatches {
+        let cells = (0..ROWS.len()).flat_map(|row| (0..2).map(move |column| cell(row, column)));
+        SearchMatches::find(query, cells, |cell_index| {
+            Some(ROWS[cell_index.row][cell_index.column].to_string())
+        })
+    }
+
+    #[rstest]
+    #[case("", vec![])]
+    #[case("r", vec![cell(0, 0), cell(2, 0)])]
+    #[case("1", vec![cell(0, 0), cell(0, 1), cell(1, 0), cell(1, 1), cell(2, 1)])]
+    #[case("k", vec![cell(0, 1), cell(2, 1)])]
+    #[case("x", vec![])]
+    fn test_find(#[case] query: &str, #[case] expected: Vec<CellIndex>) {
+        assert_eq!(find(query).cells, expected);
+    }
+
+    #[rstest]
+    #[case(None, false, Some(cell(0, 0)))]
+    #[case(None, true, Some(cell(2, 0)))]
+    #[case(Some(cell(0, 0)), false, Some(cell(2, 0)))]
+    #[case(Some(cell(2, 0)), false, Some(cell(0, 0)))]
+    #[case(Some(cell(0, 0)), true, Some(cell(2, 0)))]
+    // no-longer a match
+    #[case(Some(cell(1, 1)), false, Some(cell(0, 0)))]
+    fn test_step(
+        #[case] current: Option<CellIndex>,
+        #[case] backwards: bool,
+        #[case] expected: Option<CellIndex>,
+    ) {
+        assert_eq!(find("r").step(current, backwards), expected);
+    }
+
+    #[test]
+    fn test_rows_without_matches() {
+        // given
+        let mut search = SearchState {
+            query: "r".to_string(),
+            ..SearchState::default()
+        };
+        let inputs = SearchInputs {
+            data_revision: None,
+            dimensions: TableDimensions {
+                row_count: 3,
+                column_count: 2,
+            },
+            row_ordering: &[],
+            column_ordering: &[],
+            rows_to_filter: None,
+            columns_to_filter: None,
+        };
+
+        // when
+        let matches = search.find(inputs, find);
+
+        // then
+        assert_eq!(matches.cells, vec![cell(0, 0), cell(2, 0)]);
+        assert_eq!(matches.rows_without_matches, vec![1]);
+    }
+}
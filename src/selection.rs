@@ -0,0 +1,258 @@
+use crate::{CellIndex, TableDimensions};
+use egui::Modifiers;
+use std::collections::BTreeSet;
+use std::ops::RangeInclusive;
+
+/// A rectangular range of cells, specified using the mapped (data) indexes of two opposite corners.
+///
+/// The range covers all the cells between the two corners in the *current* row/column ordering, thus, if the rows or
+/// columns are re-ordered, the cells covered by a range change.  Use [`CellRange::indexes`] to get the covered rows
+/// and columns.
+///
+/// See also:
+/// 1. [`crate::Action::CellSelectionChanged`]
+/// 2. [`crate::DeferredTable::selectable_cells`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CellRange {
+    /// the cell that was clicked first, i.e. the anchor.
+    pub start: CellIndex,
+    /// the cell that was shift+clicked or dragged to, this is the same as `start` for a single cell.
+    pub end: CellIndex,
+}
+
+impl CellRange {
+    pub fn new(start: CellIndex, end: CellIndex) -> Self {
+        Self { start, end }
+    }
+
+    pub fn single(cell_index: CellIndex) -> Self {
+        Self::new(cell_index, cell_index)
+    }
+
+    /// returns the mapped row and column indexes covered by the range, in visible order.
+    ///
+    /// the orderings are the same as those returned by the renderer, use an empty slice when there is no ordering.
+    /// Note: the rows and columns are not filtered.
+    pub fn indexes(
+        &self,
+        dimensions: TableDimensions,
+        row_ordering: &[usize],
+        column_ordering: &[usize],
+    ) -> (Vec<usize>, Vec<usize>) {
+        let (rows, columns) = self.visible_ranges(dimensions, row_ordering, column_ordering);
+
+        (
+            rows.map(|visible_index| map_index(dimensions.row_count, row_ordering, visible_index))
+                .collect(),
+            columns
+                .map(|visible_index| {
+                    map_index(dimensions.column_count, column_ordering, visible_index)
+                })
+                .collect(),
+        )
+    }
+
+    /// returns the ranges of the visible row and column indexes covered by the range.
+    pub(crate) fn visible_ranges(
+        &self,
+        dimensions: TableDimensions,
+        row_ordering: &[usize],
+        column_ordering: &[usize],
+    ) -> (RangeInclusive<usize>, RangeInclusive<usize>) {
+        let [start_row, end_row] = [self.start.row, self.end.row]
+            .map(|row| visible_index(dimensions.row_count, row_ordering, row));
+        let [start_column, end_column] = [self.start.column, self.end.column]
+            .map(|column| visible_index(dimensions.column_count, column_ordering, column));
+
+        (
+            start_row.min(end_row)..=start_row.max(end_row),
+            start_column.min(end_column)..=start_column.max(end_column),
+        )
+    }
+
+    /// returns true if both corners of the range are within the dimensions.
+    pub(crate) fn is_within(&self, dimensions: TableDimensions) -> bool {
+        [self.start, self.end].iter().all(|cell_index| {
+            cell_index.row < dimensions.row_count && cell_index.column < dimensions.column_count
+        })
+    }
+}
+
+/// The visible cell selection, with the visible ranges computed once per frame, for fast lookup when rendering cells.
+pub(crate) struct VisibleCellSelection {
+    ranges: Vec<(RangeInclusive<usize>, RangeInclusive<usize>)>,
+}
+
+impl VisibleCellSelection {
+    pub(crate) fn new(
+        selection: &[CellRange],
+        dimensions: TableDimensions,
+        row_ordering: &[usize],
+        column_ordering: &[usize],
+    ) -> Self {
+        Self {
+            ranges: selection
+                .iter()
+                .map(|range| range.visible_ranges(dimensions, row_ordering, column_ordering))
+                .collect(),
+        }
+    }
+
+    pub(crate) fn contains(&self, visible_row_index: usize, visible_column_index: usize) -> bool {
+        self.ranges.iter().any(|(rows, columns)| {
+            rows.contains(&visible_row_index) && columns.contains(&visible_column_index)
+        })
+    }
+}
+
+/// Applies a click on a row/column header to the selected rows/columns, using the usual list semantics.
+///
+/// * click - selects just the row/column, which becomes the anchor.
+/// * ctrl+click - toggles the row/column, which becomes the anchor.
+/// * shift+click - selects the rows/columns from the anchor to the row/column, in visible order.  When ctrl is also
+///   held the range is added to the selection, otherwise it replaces the selection.
+///
+/// `visible_indexes` are the mapped indexes in visible order, excluding any filtered indexes.
+///
+/// Returns the new anchor.
+pub(crate) fn apply_axis_selection(
+    selection: &mut BTreeSet<usize>,
+    anchor: Option<usize>,
+    mapped_index: usize,
+    modifiers: Modifiers,
+    visible_indexes: impl Iterator<Item = usize>,
+) -> Option<usize> {
+    if modifiers.shift
+        && let Some(anchor) = anchor
+    {
+        let visible_indexes = visible_indexes.collect::<Vec<_>>();
+        let anchor_position = visible_indexes.iter().position(|index| *index == anchor);
+        let position = visible_indexes
+            .iter()
+            .position(|index| *index == mapped_index);
+
+        // the anchor could have been filtered since it was clicked, in which case it's treated as a click.
+        if let (Some(anchor_position), Some(position)) = (anchor_position, position) {
+            if !modifiers.command {
+                selection.clear();
+            }
+            let range = anchor_position.min(position)..=anchor_position.max(position);
+            selection.extend(visible_indexes[range].iter().copied());
+
+            return Some(anchor);
+        }
+    }
+
+    if modifiers.command {
+        if !selection.remove(&mapped_index) {
+            selection.insert(mapped_index);
+        }
+    } else {
+        selection.clear();
+        selection.insert(mapped_index);
+    }
+
+    Some(mapped_index)
+}
+
+/// same as `DeferredTable::map_index`
+fn map_index(count: usize, ordering: &[usize], visible_index: usize) -> usize {
+    let mapped_index = *ordering.get(visible_index).unwrap_or(&visible_index);
+    if mapped_index >= count {
+        // handle out-of-range mapping values
+        visible_index
+    } else {
+        mapped_index
+    }
+}
+
+/// the inverse of [`map_index`]
+fn visible_index(count: usize, ordering: &[usize], mapped_index: usize) -> usize {
+    if ordering.is_empty() {
+        return mapped_index;
+    }
+
+    (0..count)
+        .find(|visible_index| map_index(count, ordering, *visible_index) == mapped_index)
+        .unwrap_or(mapped_index)
+}
+
+#[cfg(test)]
+mod axis_selection_tests {
+    use crate::selection::apply_axis_selection;
+    use egui::Modifiers;
+    use rstest::rstest;
+    use std::collections::BTreeSet;
+
+    #[rstest]
+    // click
+    #[case(vec![1, 2], None, 3, Modifiers::NONE, vec![3], Some(3))]
+    // ctrl+click
+    #[case(vec![1, 2], Some(1), 3, Modifiers::COMMAND, vec![1, 2, 3], Some(3))]
+    #[case(vec![1, 2], Some(1), 2, Modifiers::COMMAND, vec![1], Some(2))]
+    // shift+click, in visible order, skipping filtered index 2
+    #[case(vec![4], Some(4), 0, Modifiers::SHIFT, vec![0, 3, 4], Some(4))]
+    #[case(vec![4], Some(3), 5, Modifiers::SHIFT, vec![3, 4, 5], Some(3))]
+    // ctrl+shift+click adds the range
+    #[case(vec![0], Some(4), 5, Modifiers::COMMAND | Modifiers::SHIFT, vec![0, 4, 5], Some(4))]
+    // shift+click without an anchor, or a filtered anchor
+    #[case(vec![1], None, 3, Modifiers::SHIFT, vec![3], Some(3))]
+    #[case(vec![1], Some(2), 3, Modifiers::SHIFT, vec![3], Some(3))]
+    fn test_apply_axis_selection(
+        #[case] selection: Vec<usize>,
+        #[case] anchor: Option<usize>,
+        #[case] mapped_index: usize,
+        #[case] modifiers: Modifiers,
+        #[case] expected_selection: Vec<usize>,
+        #[case] expected_anchor: Option<usize>,
+    ) {
+        // visible order, with index 2 filtered
+        let visible_indexes = [1, 0, 3, 4, 5];
+
+        let mut selection = BTreeSet::from_iter(selection);
+        let anchor = apply_axis_selection(
+            &mut selection,
+            anchor,
+            mapped_index,
+            modifiers,
+            visible_indexes.into_iter(),
+        );
+        assert_eq!(selection, BTreeSet::from_iter(expected_selection));
+        assert_eq!(anchor, expected_anchor);
+    }
+}
+
+#[cfg(test)]
+mod cell_range_tests {
+    use crate::{CellIndex, CellRange, TableDimensions};
+    use rstest::rstest;
+
+    const DIMENSIONS: TableDimensions = TableDimensions {
+        row_count: 4,
+        column_count: 3,
+    };
+
+    #[rstest]
+    #[case((0, 0), (1, 1), vec![], vec![], vec![0, 1], vec![0, 1])]
+    // corners in any order
+    #[case((2, 3), (1, 1), vec![], vec![], vec![1, 2, 3], vec![1, 2])]
+    // re-ordered rows, visible order is 3, 2, 1, 0
+    #[case((0, 0), (0, 2), vec![3, 2, 1, 0], vec![], vec![2, 1, 0], vec![0])]
+    // re-ordered columns, visible order is 2, 0, 1
+    #[case((2, 0), (0, 0), vec![], vec![2, 0, 1], vec![0], vec![2, 0])]
+    // partial ordering
+    #[case((0, 0), (0, 3), vec![1, 0], vec![], vec![0, 2, 3], vec![0])]
+    fn test_indexes(
+        #[case] start: (usize, usize),
+        #[case] end: (usize, usize),
+        #[case] row_ordering: Vec<usize>,
+        #[case] column_ordering: Vec<usize>,
+        #[case] expected_rows: Vec<usize>,
+        #[case] expected_columns: Vec<usize>,
+    ) {
+        let range = CellRange::new(CellIndex::from(start), CellIndex::from(end));
+        let (rows, columns) = range.indexes(DIMENSIONS, &row_ordering, &column_ordering);
+        assert_eq!(rows, expected_rows);
+        assert_eq!(columns, expected_columns);
+    }
+}
@@ -8,7 +8,7 @@ use egui::Ui;
 /// Helper for rendering tables based on tuple slices
 ///
 /// Implementations of `DeferredTableRender` for tuples with 2 to 16 elements are provided by the
-/// `impl_deferred_table_for_tuple` macro.
+/// `impl_deferred_table_for_tuple` macro.  The values are rendered, and copied, using their `Display` implementations.
 ///
 /// See crate examples.
 #[derive(Default)]
@@ -36,6 +36,14 @@ macro_rules! impl_tuple_for_size {
                     };
                 }
             }
+
+            fn cell_text(&self, cell_index: CellIndex, source: &&[($($T),*)]) -> Option<String> {
+                let row_data = source.get(cell_index.row)?;
+                match cell_index.column {
+                    $( $idx => Some(row_data.$field.to_string()), )*
+                    _ => None,
+                }
+            }
         }
     };
 }
@@ -0,0 +1,157 @@
+use std::cmp::Ordering;
+
+/// The direction of a column sort.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "persistence", derive(serde::Deserialize, serde::Serialize))]
+pub enum SortDirection {
+    Ascending,
+    Descending,
+}
+
+impl SortDirection {
+    /// The direction to use after the user clicks on a column header.
+    ///
+    /// Cycles `None -> Ascending -> Descending -> None`.
+    pub fn cycle(direction: Option<SortDirection>) -> Option<SortDirection> {
+        match direction {
+            None => Some(SortDirection::Ascending),
+            Some(SortDirection::Ascending) => Some(SortDirection::Descending),
+            Some(SortDirection::Descending) => None,
+        }
+    }
+}
+
+/// An ordered list of sorted columns, the first entry has the highest priority.
+///
+/// Columns are specified using the mapped column index.
+///
+/// See also:
+/// 1. [`crate::Action::SortChanged`]
+/// 2. [`crate::build_sort_spec_ordering`]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "persistence", derive(serde::Deserialize, serde::Serialize))]
+pub struct SortSpec {
+    keys: Vec<(usize, SortDirection)>,
+}
+
+impl SortSpec {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// the sorted columns and their directions, in priority order.
+    pub fn keys(&self) -> &[(usize, SortDirection)] {
+        &self.keys
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn clear(&mut self) {
+        self.keys.clear();
+    }
+
+    /// the direction of the column, if the column is sorted.
+    pub fn direction(&self, column: usize) -> Option<SortDirection> {
+        self.keys
+            .iter()
+            .find(|(sorted_column, _)| *sorted_column == column)
+            .map(|(_, direction)| *direction)
+    }
+
+    /// the zero-based priority of the column, if the column is sorted.
+    pub fn priority(&self, column: usize) -> Option<usize> {
+        self.keys
+            .iter()
+            .position(|(sorted_column, _)| *sorted_column == column)
+    }
+
+    /// Changes the direction of an already sorted column, adds the column with the lowest priority if it is not
+    /// sorted, or removes the column when the direction is `None`.
+    pub fn set(&mut self, column: usize, direction: Option<SortDirection>) {
+        match (self.priority(column), direction) {
+            (Some(priority), Some(direction)) => self.keys[priority].1 = direction,
+            (Some(priority), None) => {
+                self.keys.remove(priority);
+            }
+            (None, Some(direction)) => self.keys.push((column, direction)),
+            (None, None) => {}
+        }
+    }
+
+    /// Applies a click on a column header, returning the new direction of the column.
+    ///
+    /// When `extend` is false (a plain click), the column becomes the only sorted column.
+    /// When `extend` is true (a shift+click), the other sorted columns are retained.
+    ///
+    /// In both cases the direction of the column is cycled, see [`SortDirection::cycle`].
+    pub fn cycle(&mut self, column: usize, extend: bool) -> Option<SortDirection> {
+        let direction = SortDirection::cycle(self.direction(column));
+
+        if !extend {
+            self.keys
+                .retain(|(sorted_column, _)| *sorted_column == column);
+        }
+        self.set(column, direction);
+
+        direction
+    }
+}
+
+impl From<Vec<(usize, SortDirection)>> for SortSpec {
+    fn from(keys: Vec<(usize, SortDirection)>) -> Self {
+        Self { keys }
+    }
+}
+
+/// Implement this to compare the values of two rows for a given column.
+///
+/// Used by [`crate::build_sort_spec_ordering`], a blanket implementation is provided for closures with the signature
+/// `Fn(column, row_a, row_b) -> Ordering`.
+pub trait ColumnComparator {
+    /// compare the values in the column of the two (mapped) rows, in ascending order.
+    fn compare(&self, column: usize, row_a: usize, row_b: usize) -> Ordering;
+}
+
+impl<F> ColumnComparator for F
+where
+    F: Fn(usize, usize, usize) -> Ordering,
+{
+    fn compare(&self, column: usize, row_a: usize, row_b: usize) -> Ordering {
+        self(column, row_a, row_b)
+    }
+}
+
+#[cfg(test)]
+mod sort_spec_tests {
+    use crate::SortDirection::{Ascending, Descending};
+    use crate::{SortDirection, SortSpec};
+    use rstest::rstest;
+
+    #[rstest]
+    // plain click
+    #[case(vec![], 1, false, vec![(1, Ascending)])]
+    #[case(vec![(1, Ascending)], 1, false, vec![(1, Descending)])]
+    #[case(vec![(1, Descending)], 1, false, vec![])]
+    #[case(vec![(0, Ascending), (1, Ascending)], 2, false, vec![(2, Ascending)])]
+    #[case(vec![(0, Ascending), (1, Ascending)], 1, false, vec![(1, Descending)])]
+    // shift+click
+    #[case(vec![(0, Ascending)], 1, true, vec![(0, Ascending), (1, Ascending)])]
+    #[case(vec![(0, Ascending), (1, Ascending)], 0, true, vec![(0, Descending), (1, Ascending)])]
+    #[case(vec![(0, Descending), (1, Ascending)], 0, true, vec![(1, Ascending)])]
+    fn test_cycle(
+        #[case] keys: Vec<(usize, SortDirection)>,
+        #[case] column: usize,
+        #[case] extend: bool,
+        #[case] expected: Vec<(usize, SortDirection)>,
+    ) {
+        let mut spec = SortSpec::from(keys);
+        spec.cycle(column, extend);
+        assert_eq!(spec.keys(), expected.as_slice());
+    }
+}
@@ -0,0 +1,370 @@
+use crate::CellIndex;
+use crate::ordering::map_index;
+use std::collections::BTreeSet;
+use std::ops::Range;
+
+/// A cell merged with the cells below and/or to the right of it, see [`crate::DeferredTableDataSource::cell_span`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CellSpan {
+    /// the mapped index of the top-left cell of the span.
+    pub origin: CellIndex,
+    /// the number of rows spanned, in visible order, filtered rows are not counted.
+    pub rows: usize,
+    /// the number of columns spanned, in visible order, filtered columns are not counted.
+    pub columns: usize,
+}
+
+/// An axis of the table, as needed to find the spans.
+pub(crate) struct SpanAxis<'a> {
+    count: usize,
+    ordering: &'a [usize],
+    filter: BTreeSet<usize>,
+    /// the visible indexes before which spans end, e.g. the edges of the pinned bands.
+    breaks: BTreeSet<usize>,
+    /// the visible indexes that are shown, including filtered indexes.
+    shown: &'a [usize],
+}
+
+impl<'a> SpanAxis<'a> {
+    pub(crate) fn new(
+        count: usize,
+        ordering: &'a [usize],
+        filter: Option<&[usize]>,
+        breaks: impl IntoIterator<Item = usize>,
+        shown: &'a [usize],
+    ) -> Self {
+        Self {
+            count,
+            ordering,
+            filter: filter.unwrap_or_default().iter().copied().collect(),
+            breaks: breaks.into_iter().collect(),
+            shown,
+        }
+    }
+
+    fn map_index(&self, visible_index: usize) -> usize {
+        map_index(self.count, self.ordering, visible_index)
+    }
+
+    /// the visible index of a mapped index, the inverse of `map_index`.
+    fn visible_index(&self, index: usize) -> Option<usize> {
+        self.ordering
+            .iter()
+            .position(|mapped_index| *mapped_index == index)
+            .or_else(|| {
+                (self.ordering.len()..self.count)
+                    .contains(&index)
+                    .then_some(index)
+            })
+    }
+
+    fn is_filtered(&self, visible_index: usize) -> bool {
+        self.filter.contains(&self.map_index(visible_index))
+    }
+
+    /// the range of visible indexes between the breaks containing the visible index.
+    fn band(&self, visible_index: usize) -> Range<usize> {
+        let start = self.breaks.range(..=visible_index).next_back().copied();
+        let end = self.breaks.range(visible_index + 1..).next().copied();
+        start.unwrap_or(0)..end.unwrap_or(self.count)
+    }
+
+    /// the range of visible indexes covered by a span of `count` non-filtered indexes starting at the visible index.
+    fn extent(&self, visible_index: usize, count: usize) -> Range<usize> {
+        let band = self.band(visible_index);
+        let mut remaining = count;
+        let mut end = visible_index;
+        while end < band.end && remaining > 0 {
+            if !self.is_filtered(end) {
+                remaining -= 1;
+            }
+            end += 1;
+        }
+        visible_index..end
+    }
+}
+
+/// A span that covers shown cells.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ShownSpan {
+    /// the mapped index of the top-left cell of the span.
+    pub(crate) origin: CellIndex,
+    /// the visible row indexes covered by the span, including filtered rows.
+    pub(crate) rows: Range<usize>,
+    /// the visible column indexes covered by the span, including filtered columns.
+    pub(crate) columns: Range<usize>,
+}
+
+/// The spans that cover the shown cells.
+#[derive(Debug, Default)]
+pub(crate) struct CellSpans {
+    spans: Vec<ShownSpan>,
+}
+
+impl CellSpans {
+    /// `span` returns the span covering the cell with the mapped index, if any, it's called for the shown cells that
+    /// are not covered by the spans found so far.
+    pub(crate) fn new(
+        rows: &SpanAxis,
+        columns: &SpanAxis,
+        span: impl Fn(CellIndex) -> Option<CellSpan>,
+    ) -> Self {
+        let mut spans = Self::default();
+
+        for &visible_row_index in rows.shown {
+            for &visible_column_index in columns.shown {
+                if rows.is_filtered(visible_row_index)
+                    || columns.is_filtered(visible_column_index)
+                    || spans
+                        .find(visible_row_index, visible_column_index)
+                        .is_some()
+                {
+                    continue;
+                }
+                let cell_index = CellIndex {
+                    row: rows.map_index(visible_row_index),
+                    column: columns.map_index(visible_column_index),
+                };
+                let Some(cell_span) = span(cell_index) else {
+                    continue;
+                };
+                if cell_span.rows <= 1 && cell_span.columns <= 1 {
+                    continue;
+                }
+
+                // the origin may not be shown, e.g. when it's scrolled off-screen.
+                let origin = match cell_span.origin == cell_index {
+                    true => Some((visible_row_index, visible_column_index)),
+                    false => rows
+                        .visible_index(cell_span.origin.row)
+                        .zip(columns.visible_index(cell_span.origin.column)),
+                };
+                let Some((origin_row_index, origin_column_index)) = origin else {
+                    continue;
+                };
+                if rows.is_filtered(origin_row_index) || columns.is_filtered(origin_column_index) {
+                    continue;
+                }
+
+                let shown_span = ShownSpan {
+                    origin: cell_span.origin,
+                    rows: rows.extent(origin_row_index, cell_span.rows),
+                    columns: columns.extent(origin_column_index, cell_span.columns),
+                };
+                // the cell is not covered if the ordering or the pinned bands separate it from the origin.
+                if shown_span.rows.contains(&visible_row_index)
+                    && shown_span.columns.contains(&visible_column_index)
+                {
+                    spans.spans.push(shown_span);
+                }
+            }
+        }
+
+        spans
+    }
+
+    /// returns the span covering the cell with the visible row and column indexes, if any.
+    pub(crate) fn find(
+        &self,
+        visible_row_index: usize,
+        visible_column_index: usize,
+    ) -> Option<&ShownSpan> {
+        self.spans.iter().find(|span| {
+            span.rows.contains(&visible_row_index) && span.columns.contains(&visible_column_index)
+        })
+    }
+}
+
+#[cfg(test)]
+mod cell_span_tests {
+    use crate::CellIndex;
+    use crate::spans::{CellSpan, CellSpans, ShownSpan, SpanAxis};
+
+    fn axis<'a>(
+        ordering: &'a [usize],
+        filter: Option<&[usize]>,
+        scrolled: std::ops::Range<usize>,
+        shown: &'a [usize],
+    ) -> SpanAxis<'a> {
+        SpanAxis::new(10, ordering, filter, [scrolled.start, scrolled.end], shown)
+    }
+
+    /// returns the span covering the cell, where the spans cover the following mapped indexes.
+    fn covering(spans: &[CellSpan]) -> impl Fn(CellIndex) -> Option<CellSpan> {
+        move |cell_index| {
+            spans.iter().copied().find(|span| {
+                (span.origin.row..span.origin.row.saturating_add(span.rows))
+                    .contains(&cell_index.row)
+                    && (span.origin.column..span.origin.column.saturating_add(span.columns))
+                        .contains(&cell_index.column)
+            })
+        }
+    }
+
+    const SPANS: [CellSpan; 2] = [
+        // a section title, spanning all the columns
+        CellSpan {
+            origin: CellIndex { row: 0, column: 0 },
+            rows: 1,
+            columns: 10,
+        },
+        // spanning 3 rows
+        CellSpan {
+            origin: CellIndex { row: 4, column: 2 },
+            rows: 3,
+            columns: 1,
+        },
+    ];
+
+    #[test]
+    fn test_spans() {
+        // given
+        let rows = axis(&[], None, 0..10, &[0, 1, 2, 3, 4, 5]);
+        let columns = axis(&[], None, 0..10, &[0, 1, 2, 3]);
+
+        // when
+        let spans = CellSpans::new(&rows, &columns, covering(&SPANS));
+
+        // then
+        assert_eq!(
+            spans.find(0, 3),
+            Some(&ShownSpan {
+                origin: CellIndex { row: 0, column: 0 },
+                rows: 0..1,
+                columns: 0..10,
+            })
+        );
+        assert_eq!(
+            spans.find(6, 2),
+            Some(&ShownSpan {
+                origin: CellIndex { row: 4, column: 2 },
+                rows: 4..7,
+                columns: 2..3,
+            })
+        );
+        assert_eq!(spans.find(3, 2), None);
+        assert_eq!(spans.find(5, 3), None);
+    }
+
+    #[test]
+    fn test_origin_scrolled_off_screen() {
+        // given
+        let rows = axis(&[], None, 0..10, &[5, 6, 7]);
+        let columns = axis(&[], None, 0..10, &[3, 4]);
+        let span = CellSpan {
+            origin: CellIndex { row: 4, column: 2 },
+            rows: 3,
+            columns: 2,
+        };
+
+        // when
+        let spans = CellSpans::new(&rows, &columns, covering(&[span]));
+
+        // then
+        assert_eq!(
+            spans.find(6, 3).map(|span| span.origin),
+            Some(CellIndex { row: 4, column: 2 })
+        );
+        assert_eq!(spans.find(7, 3), None);
+    }
+
+    #[test]
+    fn test_reordered_and_filtered() {
+        // given
+        // row 4 is shown first, row 9 is filtered, so the span covers rows 4, 9, 0 and 1.
+        let ordering = [4, 9, 0, 1, 2, 3, 5, 6, 7, 8];
+        let filter = [9];
+        let rows = axis(&ordering, Some(&filter), 0..10, &[0, 1, 2, 3]);
+        let columns = axis(&[], None, 0..10, &[0, 1, 2]);
+        let span = CellSpan {
+            origin: CellIndex { row: 4, column: 2 },
+            rows: 3,
+            columns: 1,
+        };
+
+        // when
+        let spans = CellSpans::new(&rows, &columns, |cell_index: CellIndex| {
+            ([4, 0, 1].contains(&cell_index.row) && cell_index.column == 2).then_some(span)
+        });
+
+        // then
+        assert_eq!(spans.find(3, 2).map(|span| span.rows.clone()), Some(0..4));
+        assert_eq!(spans.find(4, 2), None);
+    }
+
+    #[test]
+    fn test_span_ends_at_pinned_band() {
+        // given
+        let rows = axis(&[], None, 0..10, &[0]);
+        // columns 8 and 9 are pinned to the end
+        let columns = axis(&[], None, 0..8, &[0, 1, 8, 9]);
+
+        // when
+        let spans = CellSpans::new(&rows, &columns, covering(&SPANS));
+
+        // then
+        assert_eq!(
+            spans.find(0, 7).map(|span| span.columns.clone()),
+            Some(0..8)
+        );
+        assert_eq!(spans.find(0, 8), None);
+    }
+
+    #[test]
+    fn test_span_ends_at_break() {
+        // given
+        // a group header row is shown above row 3
+        let rows = SpanAxis::new(10, &[], None, [3], &[0, 1, 2, 3, 4]);
+        let columns = axis(&[], None, 0..10, &[0]);
+        let span = CellSpan {
+            origin: CellIndex { row: 1, column: 0 },
+            rows: 4,
+            columns: 1,
+        };
+
+        // when
+        let spans = CellSpans::new(&rows, &columns, covering(&[span]));
+
+        // then
+        assert_eq!(spans.find(2, 0).map(|span| span.rows.clone()), Some(1..3));
+        assert_eq!(spans.find(3, 0), None);
+    }
+
+    #[test]
+    fn test_far_origin() {
+        // given
+        // a span of 60 rows, starting at row 0, where rows 50 and 51 are shown
+        let rows = SpanAxis::new(100, &[], None, [], &[50, 51]);
+        let columns = axis(&[], None, 0..10, &[0, 1]);
+        let span = CellSpan {
+            origin: CellIndex { row: 0, column: 0 },
+            rows: 60,
+            columns: 1,
+        };
+
+        // when
+        let spans = CellSpans::new(&rows, &columns, covering(&[span]));
+
+        // then
+        assert_eq!(spans.find(50, 0).map(|span| span.rows.clone()), Some(0..60));
+        assert_eq!(spans.find(50, 1), None);
+    }
+
+    #[test]
+    fn test_huge_span() {
+        // given
+        let rows = axis(&[], None, 0..10, &[0, 1]);
+        let columns = axis(&[], None, 0..10, &[0, 1]);
+        let span = CellSpan {
+            origin: CellIndex { row: 0, column: 0 },
+            rows: usize::MAX,
+            columns: 2,
+        };
+
+        // when
+        let spans = CellSpans::new(&rows, &columns, covering(&[span]));
+
+        // then
+        assert_eq!(spans.find(1, 1).map(|span| span.rows.clone()), Some(0..10));
+    }
+}
@@ -0,0 +1,203 @@
+use crate::{CellIndex, DeferredTablePersistentState, DeferredTableTempState};
+use egui::{Context, Id};
+use std::collections::BTreeSet;
+
+pub(crate) fn temp_state_id(table_id: Id) -> Id {
+    table_id.with("temp_state")
+}
+
+pub(crate) fn persistent_state_id(table_id: Id) -> Id {
+    table_id.with("persistent_state")
+}
+
+pub(crate) fn keys_id(table_id: Id) -> Id {
+    table_id.with("keys")
+}
+
+/// A handle to the state of a table, use it to inspect or change the state of a table outside of rendering it.
+///
+/// Changes are applied the next time the table is shown, no actions are generated for them.
+///
+/// ```text
+/// let mut state = DeferredTableState::load(ui.ctx(), table_id);
+/// state.set_row_selection([inserted_row]);
+/// state.scroll_to(CellIndex { row: inserted_row, column: 0 });
+/// state.store(ui.ctx());
+/// ```
+pub struct DeferredTableState {
+    id: Id,
+    temp_state: DeferredTableTempState,
+    persistent_state: DeferredTablePersistentState,
+}
+
+impl DeferredTableState {
+    /// `id` is the id of the table, as used with [`crate::DeferredTable::new`].
+    pub fn load(ctx: &Context, id: Id) -> Self {
+        Self {
+            id,
+            temp_state: DeferredTableTempState::load_or_default(ctx, temp_state_id(id)),
+            persistent_state: DeferredTablePersistentState::load_or_default(
+                ctx,
+                persistent_state_id(id),
+            ),
+        }
+    }
+
+    /// store the state, so that the changes are used the next time the table is shown.
+    pub fn store(self, ctx: &Context) {
+        DeferredTableTempState::store(ctx, temp_state_id(self.id), self.temp_state);
+        DeferredTablePersistentState::store(
+            ctx,
+            persistent_state_id(self.id),
+            self.persistent_state,
+        );
+    }
+
+    /// the mapped indexes of the selected rows.
+    pub fn selected_rows(&self) -> &BTreeSet<usize> {
+        &self.temp_state.row_selections
+    }
+
+    /// replaces the row selection, using mapped row indexes.
+    pub fn set_row_selection(&mut self, selection: impl IntoIterator<Item = usize>) {
+        self.temp_state.row_selections = selection.into_iter().collect();
+        self.temp_state.row_selection_anchor = None;
+    }
+
+    /// the mapped indexes of the collapsed rows, see [`crate::DeferredTableDataSource::tree_row`].
+    pub fn collapsed_rows(&self) -> &BTreeSet<usize> {
+        &self.temp_state.collapsed_rows
+    }
+
+    /// collapses or expands a row, using a mapped row index, see [`crate::DeferredTableDataSource::tree_row`].
+    pub fn set_row_expanded(&mut self, row: usize, expanded: bool) {
+        if expanded {
+            self.temp_state.collapsed_rows.remove(&row);
+        } else {
+            self.temp_state.collapsed_rows.insert(row);
+        }
+    }
+
+    /// the keys of the collapsed row groups, see [`crate::DeferredTable::group_rows_by`].
+    pub fn collapsed_groups(&self) -> &BTreeSet<String> {
+        &self.temp_state.collapsed_groups
+    }
+
+    /// collapses or expands the row groups with the key, see [`crate::DeferredTable::group_rows_by`].
+    pub fn set_group_expanded(&mut self, key: impl Into<String>, expanded: bool) {
+        let key = key.into();
+        if expanded {
+            self.temp_state.collapsed_groups.remove(&key);
+        } else {
+            self.temp_state.collapsed_groups.insert(key);
+        }
+    }
+
+    /// clears the row, column and cell selections.
+    pub fn clear_selection(&mut self) {
+        let temp_state = &mut self.temp_state;
+        temp_state.row_selections.clear();
+        temp_state.row_selection_anchor = None;
+        temp_state.column_selections.clear();
+        temp_state.column_selection_anchor = None;
+        temp_state.cell_selections.clear();
+    }
+
+    /// sets the widths of the first `widths.len()` columns, using mapped column indexes.
+    ///
+    /// The widths are used as-is, they are not clamped to the column's [`crate::AxisParameters::dimension_range`].
+    pub fn set_column_widths(&mut self, widths: &[f32]) {
+        let column_widths = &mut self.persistent_state.column_widths;
+        if column_widths.len() < widths.len() {
+            column_widths.resize(widths.len(), 0.0);
+        }
+        column_widths[..widths.len()].copy_from_slice(widths);
+    }
+
+    /// scrolls the table, if needed, so that the cell is fully visible.  The cell is specified using mapped indexes.
+    pub fn scroll_to(&mut self, cell_index: CellIndex) {
+        self.temp_state.scroll_to = Some(cell_index);
+    }
+}
+
+#[cfg(test)]
+mod state_tests {
+    use crate::{CellIndex, DeferredTableState};
+    use egui::{Context, Id};
+    use rstest::rstest;
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn test_store_and_load() {
+        // given
+        let ctx = Context::default();
+        let id = Id::new("table");
+
+        let mut state = DeferredTableState::load(&ctx, id);
+        state.set_row_selection([3, 1]);
+        state.scroll_to(CellIndex { row: 3, column: 0 });
+        state.store(&ctx);
+
+        // when
+        let state = DeferredTableState::load(&ctx, id);
+
+        // then
+        assert_eq!(state.selected_rows(), &BTreeSet::from([1, 3]));
+        assert_eq!(
+            state.temp_state.scroll_to,
+            Some(CellIndex { row: 3, column: 0 })
+        );
+    }
+
+    #[test]
+    fn test_clear_selection() {
+        let mut state = DeferredTableState::load(&Context::default(), Id::new("table"));
+        state.set_row_selection([1, 2]);
+
+        state.clear_selection();
+
+        assert!(state.selected_rows().is_empty());
+    }
+
+    #[test]
+    fn test_set_row_expanded() {
+        let mut state = DeferredTableState::load(&Context::default(), Id::new("table"));
+
+        state.set_row_expanded(1, false);
+        state.set_row_expanded(3, false);
+        state.set_row_expanded(1, true);
+
+        assert_eq!(state.collapsed_rows(), &BTreeSet::from([3]));
+    }
+
+    #[test]
+    fn test_set_group_expanded() {
+        let mut state = DeferredTableState::load(&Context::default(), Id::new("table"));
+
+        state.set_group_expanded("0603", false);
+        state.set_group_expanded("0805", false);
+        state.set_group_expanded("0603", true);
+
+        assert_eq!(
+            state.collapsed_groups(),
+            &BTreeSet::from(["0805".to_string()])
+        );
+    }
+
+    #[rstest]
+    #[case(vec![], vec![100.0], vec![100.0])]
+    #[case(vec![10.0, 20.0, 30.0], vec![100.0], vec![100.0, 20.0, 30.0])]
+    #[case(vec![10.0], vec![100.0, 200.0], vec![100.0, 200.0])]
+    fn test_set_column_widths(
+        #[case] column_widths: Vec<f32>,
+        #[case] widths: Vec<f32>,
+        #[case] expected: Vec<f32>,
+    ) {
+        let mut state = DeferredTableState::load(&Context::default(), Id::new("table"));
+        state.persistent_state.column_widths = column_widths;
+
+        state.set_column_widths(&widths);
+
+        assert_eq!(state.persistent_state.column_widths, expected);
+    }
+}
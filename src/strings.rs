@@ -0,0 +1,112 @@
+/// The strings used by the built-in UI of the table, e.g. the search bar, the filter popups, the column header menu and
+/// the column chooser, and the default error messages, e.g. of [`crate::Action::Pasted`].
+///
+/// Every method has an English default, override them to translate the strings, e.g. using an i18n crate.
+///
+/// See also:
+/// 1. [`crate::DeferredTable::strings`]
+pub trait DeferredTableStrings {
+    fn search_hint(&self) -> String {
+        "🔍 search".to_string()
+    }
+
+    fn previous_match(&self) -> String {
+        "Previous match (shift+enter)".to_string()
+    }
+
+    fn next_match(&self) -> String {
+        "Next match (enter)".to_string()
+    }
+
+    fn filter_to_matches(&self) -> String {
+        "filter to matches".to_string()
+    }
+
+    fn filter_contains(&self) -> String {
+        "contains".to_string()
+    }
+
+    fn filter_equals(&self) -> String {
+        "equals".to_string()
+    }
+
+    fn filter_regex(&self) -> String {
+        "regex".to_string()
+    }
+
+    /// the label of the case-sensitive toggle of text filters
+    fn filter_case_sensitive(&self) -> String {
+        "Aa".to_string()
+    }
+
+    /// the label of the minimum bound of number filters
+    fn filter_minimum(&self) -> String {
+        "≥".to_string()
+    }
+
+    /// the label of the maximum bound of number filters
+    fn filter_maximum(&self) -> String {
+        "≤".to_string()
+    }
+
+    fn sort_ascending(&self) -> String {
+        "Sort ascending".to_string()
+    }
+
+    fn sort_descending(&self) -> String {
+        "Sort descending".to_string()
+    }
+
+    fn hide_column(&self) -> String {
+        "Hide column".to_string()
+    }
+
+    fn auto_fit_width(&self) -> String {
+        "Auto-fit width".to_string()
+    }
+
+    fn reset_width(&self) -> String {
+        "Reset width".to_string()
+    }
+
+    fn pin_left(&self) -> String {
+        "Pin left".to_string()
+    }
+
+    fn pin_right(&self) -> String {
+        "Pin right".to_string()
+    }
+
+    fn unpin(&self) -> String {
+        "Unpin".to_string()
+    }
+
+    /// the label of the column chooser sub-menu of the column header menu
+    fn columns(&self) -> String {
+        "Columns".to_string()
+    }
+
+    fn move_column_up(&self) -> String {
+        "Move up".to_string()
+    }
+
+    fn move_column_down(&self) -> String {
+        "Move down".to_string()
+    }
+
+    /// the error message of a pasted cell that does not support pasting, see [`crate::ApplyTextError::NotSupported`]
+    fn paste_not_supported(&self) -> String {
+        "Pasting is not supported".to_string()
+    }
+
+    /// the label of a row group header, `count` is the number of rows in the group
+    fn group_label(&self, key: &str, count: usize) -> String {
+        format!("{} ({})", key, count)
+    }
+}
+
+/// The default, English, strings.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct DefaultStrings;
+
+impl DeferredTableStrings for DefaultStrings {}
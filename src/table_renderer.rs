@@ -1,4 +1,4 @@
-use crate::CellIndex;
+use crate::{Axis, CellIndex};
 use egui::Ui;
 
 pub trait DeferredTableRenderer<DataSource> {
@@ -33,4 +33,27 @@ pub trait DeferredTableRenderer<DataSource> {
     fn column_ordering(&self) -> Option<&[usize]> {
         None
     }
+
+    /// return the text of the cell, used when copying cells to the clipboard, see [`crate::DeferredTable::copy_format`].
+    ///
+    /// called for each copied cell, cells that return `None` are copied as empty cells.  Nothing is copied when all the
+    /// copied cells return `None`, which is the default.
+    fn cell_text(&self, _cell_index: CellIndex, _source: &DataSource) -> Option<String> {
+        None
+    }
+
+    /// add the contents of the context menu for a cell, shown when the cell is secondary-clicked (right-clicked) or
+    /// long-pressed.  The menu is closed when a button in it is clicked.
+    ///
+    /// Call `ui.close()` to not show a menu for the cell, the default implementation does this for all cells.
+    fn cell_context_menu(&self, ui: &mut Ui, _cell_index: CellIndex, _source: &DataSource) {
+        ui.close();
+    }
+
+    /// add the contents of the context menu for a column or row header, `index` is the mapped column/row index.
+    ///
+    /// Call `ui.close()` to not show a menu for the header, the default implementation does this for all headers.
+    fn header_context_menu(&self, ui: &mut Ui, _axis: Axis, _index: usize) {
+        ui.close();
+    }
 }
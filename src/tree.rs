@@ -0,0 +1,125 @@
+use std::collections::BTreeSet;
+
+/// The position of a row in a tree of rows, see [`crate::DeferredTableDataSource::tree_row`].
+///
+/// e.g. an assembly with sub-assemblies, where the rows of the sub-assemblies follow the row of the assembly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TreeRow {
+    /// the nesting level of the row, root rows are at depth 0.
+    pub depth: usize,
+    /// the mapped index of the parent row, `None` for root rows.
+    pub parent: Option<usize>,
+    /// true if the row has child rows, only rows with children show a disclosure triangle.
+    pub has_children: bool,
+}
+
+impl TreeRow {
+    pub fn root(has_children: bool) -> Self {
+        Self {
+            depth: 0,
+            parent: None,
+            has_children,
+        }
+    }
+
+    pub fn child(parent: usize, depth: usize, has_children: bool) -> Self {
+        Self {
+            depth,
+            parent: Some(parent),
+            has_children,
+        }
+    }
+}
+
+/// returns the mapped indexes of the rows to filter, i.e. the rows in `filter` and the descendants of the collapsed rows.
+///
+/// `parent` returns the mapped index of the parent of the row with the mapped index, if any.
+pub(crate) fn collapsed_rows_to_filter(
+    count: usize,
+    collapsed: &BTreeSet<usize>,
+    filter: Option<&[usize]>,
+    parent: impl Fn(usize) -> Option<usize>,
+) -> Vec<usize> {
+    // holds whether each row is hidden, once known
+    let mut hidden: Vec<Option<bool>> = vec![None; count];
+
+    for index in 0..count {
+        // the rows visited while walking up the tree, these are all hidden, or all not hidden.
+        let mut rows = vec![];
+        let mut current = index;
+        let is_hidden = loop {
+            if let Some(is_hidden) = hidden[current] {
+                break is_hidden;
+            }
+            rows.push(current);
+            match parent(current) {
+                // note: the length check stops the walk if the parents contain a cycle
+                Some(parent) if parent < count && rows.len() <= count => {
+                    if collapsed.contains(&parent) {
+                        break true;
+                    }
+                    current = parent;
+                }
+                _ => break false,
+            }
+        };
+        for row in rows {
+            hidden[row] = Some(is_hidden);
+        }
+    }
+
+    let mut rows = filter
+        .unwrap_or_default()
+        .iter()
+        .copied()
+        .filter(|index| *index < count)
+        .collect::<BTreeSet<_>>();
+    rows.extend((0..count).filter(|index| hidden[*index] == Some(true)));
+    rows.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tree_tests {
+    use crate::tree::collapsed_rows_to_filter;
+    use rstest::rstest;
+    use std::collections::BTreeSet;
+
+    // 0
+    // ├─ 1
+    // │  ├─ 2
+    // │  └─ 3
+    // └─ 4
+    // 5
+    // └─ 6
+    const PARENTS: [Option<usize>; 7] = [None, Some(0), Some(1), Some(1), Some(0), None, Some(5)];
+
+    #[rstest]
+    #[case(&[], None, vec![])]
+    #[case(&[1], None, vec![2, 3])]
+    #[case(&[0], None, vec![1, 2, 3, 4])]
+    // collapsing a descendant of a collapsed row makes no difference
+    #[case(&[0, 1], None, vec![1, 2, 3, 4])]
+    #[case(&[1, 5], None, vec![2, 3, 6])]
+    // collapsing a row without children makes no difference
+    #[case(&[4], None, vec![])]
+    // the filtered rows are included
+    #[case(&[1], Some(&[5][..]), vec![2, 3, 5])]
+    fn test_collapsed_rows_to_filter(
+        #[case] collapsed: &[usize],
+        #[case] filter: Option<&[usize]>,
+        #[case] expected: Vec<usize>,
+    ) {
+        let collapsed = collapsed.iter().copied().collect::<BTreeSet<_>>();
+        let rows =
+            collapsed_rows_to_filter(PARENTS.len(), &collapsed, filter, |index| PARENTS[index]);
+        assert_eq!(rows, expected);
+    }
+
+    #[test]
+    fn test_cycle() {
+        // the walk up the tree must stop
+        let rows =
+            collapsed_rows_to_filter(3, &BTreeSet::new(), None, |index| Some((index + 1) % 3));
+        assert_eq!(rows, Vec::<usize>::new());
+    }
+}